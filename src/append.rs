@@ -0,0 +1,49 @@
+use crate::{slice_output, Float};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Print a floating point number onto the end of a `Vec<u8>`.
+///
+/// The output is the same as that of [`Buffer::format`][crate::Buffer::format].
+/// Room for [`Float::MAX_LEN`] bytes is reserved and the number is written
+/// directly into the vector's spare capacity.
+///
+/// *This function is available if dtoa is built with the `"alloc"`
+/// feature.*
+///
+/// # Example
+///
+/// ```
+/// let mut vec = b"x=".to_vec();
+/// dtoa::append(&mut vec, 1.5);
+/// assert_eq!(vec, b"x=1.5");
+/// ```
+pub fn append<F: Float>(vec: &mut Vec<u8>, value: F) {
+    vec.reserve(F::MAX_LEN);
+    unsafe {
+        let len = vec.len();
+        let written = slice_output::format_raw(value, vec.as_mut_ptr().add(len));
+        vec.set_len(len + written);
+    }
+}
+
+/// Print a floating point number onto the end of a `String`.
+///
+/// The output is the same as that of [`Buffer::format`][crate::Buffer::format].
+/// Room for [`Float::MAX_LEN`] bytes is reserved and the number is written
+/// directly into the string's spare capacity.
+///
+/// *This function is available if dtoa is built with the `"alloc"`
+/// feature.*
+///
+/// # Example
+///
+/// ```
+/// let mut string = String::from("x=");
+/// dtoa::append_string(&mut string, 1.5);
+/// assert_eq!(string, "x=1.5");
+/// ```
+pub fn append_string<F: Float>(string: &mut String, value: F) {
+    // The output is ASCII.
+    append(unsafe { string.as_mut_vec() }, value);
+}
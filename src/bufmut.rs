@@ -0,0 +1,59 @@
+use crate::{slice_output, Buffer, Float};
+use bytes::BufMut;
+
+/// Extension methods for printing floating point numbers into a
+/// [`bytes::BufMut`].
+///
+/// *This trait is available if dtoa is built with the `"bytes"` feature.*
+///
+/// # Example
+///
+/// ```
+/// use bytes::BytesMut;
+/// use dtoa::BufMutExt;
+///
+/// let mut frame = BytesMut::new();
+/// frame.put_f64_text(1.5);
+/// frame.put_f32_text(0.1);
+/// assert_eq!(frame, &b"1.50.1"[..]);
+/// ```
+pub trait BufMutExt: BufMut {
+    /// Print an `f64` into the buffer, with the same output as
+    /// [`Buffer::format`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough remaining capacity, like
+    /// [`BufMut::put_slice`].
+    fn put_f64_text(&mut self, value: f64) {
+        put_text(self, value);
+    }
+
+    /// Print an `f32` into the buffer, with the same output as
+    /// [`Buffer::format`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not enough remaining capacity, like
+    /// [`BufMut::put_slice`].
+    fn put_f32_text(&mut self, value: f32) {
+        put_text(self, value);
+    }
+}
+
+impl<B: BufMut + ?Sized> BufMutExt for B {}
+
+fn put_text<B: BufMut + ?Sized, F: Float>(buf: &mut B, value: F) {
+    let chunk = buf.chunk_mut();
+    if chunk.len() >= F::MAX_LEN {
+        // Write straight into the chunk.
+        unsafe {
+            let written = slice_output::format_raw(value, chunk.as_mut_ptr());
+            buf.advance_mut(written);
+        }
+    } else {
+        // The output may straddle chunks.
+        let mut buffer = Buffer::new();
+        buf.put_slice(buffer.format(value).as_bytes());
+    }
+}
@@ -0,0 +1,295 @@
+// A `const fn` counterpart of `Buffer::format`, used by `Formatted::from_f64`
+// and `Formatted::from_f32`. It shares the DiyFp arithmetic and cached powers
+// with the runtime Grisu2 in diyfp.rs and the choice of notation with
+// `dtoa::prettify`. Only the steps that write digits are separate, because
+// const fn on the minimum supported compiler cannot write through a pointer:
+// here the output array is passed and returned by value.
+//
+// The output is always identical to the runtime code.
+
+use crate::dtoa::{self, Notation};
+
+// Longest output of either type, the same as `<f64 as Float>::MAX_LEN`.
+pub const LEN: usize = 25;
+
+macro_rules! const_dtoa {
+    (
+        module: $module:ident,
+        grisu: $grisu:ident,
+        floating_type: $fty:ty,
+        significand_type: $sigty:ty,
+        exponent_type: $expty:ty,
+
+        sign_mask: $sign_mask:expr,
+        exponent_mask: $exponent_mask:expr,
+        significand_mask: $significand_mask:expr,
+    ) => {
+        pub mod $module {
+            use super::{nonfinite, prettify, LEN};
+            use crate::dtoa::count_decimal_digit32;
+            use crate::$grisu::get_cached_power;
+            use core::mem;
+
+            type DiyFp = crate::diyfp::DiyFp<$sigty, $expty>;
+
+            const POW10: [$sigty; 10] = [
+                1, 10, 100, 1000, 10000, 100000, 1000000, 10000000, 100000000, 1000000000,
+            ];
+
+            const fn grisu_round(
+                mut buffer: [u8; LEN],
+                end: usize,
+                delta: $sigty,
+                mut rest: $sigty,
+                ten_kappa: $sigty,
+                wp_w: $sigty,
+            ) -> [u8; LEN] {
+                while rest < wp_w
+                    && delta - rest >= ten_kappa
+                    && (rest + ten_kappa < wp_w || // closer
+                        wp_w - rest > rest + ten_kappa - wp_w)
+                {
+                    buffer[end - 1] -= 1;
+                    rest += ten_kappa;
+                }
+                buffer
+            }
+
+            // Writes digits starting at `start` and returns length and k.
+            const fn digit_gen(
+                w: DiyFp,
+                mp: DiyFp,
+                mut delta: $sigty,
+                mut buffer: [u8; LEN],
+                start: usize,
+                mut k: isize,
+            ) -> ([u8; LEN], isize, isize) {
+                let one = DiyFp::new(1 << -mp.e, mp.e);
+                let wp_w = mp.f - w.f;
+                let mut p1 = (mp.f >> -one.e) as u32;
+                let mut p2 = mp.f & (one.f - 1);
+                let mut kappa = count_decimal_digit32(p1) as isize; // kappa in [0, 9]
+                let mut len = 0;
+
+                while kappa > 0 {
+                    let pow10 = POW10[kappa as usize - 1] as u32;
+                    let d = p1 / pow10;
+                    p1 %= pow10;
+                    if d != 0 || len != 0 {
+                        buffer[start + len] = b'0' + d as u8;
+                        len += 1;
+                    }
+                    kappa -= 1;
+                    let tmp = ((p1 as $sigty) << -one.e) + p2;
+                    if tmp <= delta {
+                        k += kappa;
+                        let ten_kappa = POW10[kappa as usize] << -one.e;
+                        buffer = grisu_round(buffer, start + len, delta, tmp, ten_kappa, wp_w);
+                        return (buffer, len as isize, k);
+                    }
+                }
+
+                // kappa = 0
+                loop {
+                    p2 *= 10;
+                    delta *= 10;
+                    let d = (p2 >> -one.e) as u8;
+                    if d != 0 || len != 0 {
+                        buffer[start + len] = b'0' + d;
+                        len += 1;
+                    }
+                    p2 &= one.f - 1;
+                    kappa -= 1;
+                    if p2 < delta {
+                        k += kappa;
+                        let index = -kappa;
+                        let wp_w = wp_w * if index < 9 { POW10[index as usize] } else { 0 };
+                        buffer = grisu_round(buffer, start + len, delta, p2, one.f, wp_w);
+                        return (buffer, len as isize, k);
+                    }
+                }
+            }
+
+            const fn grisu2(
+                bits: $sigty,
+                buffer: [u8; LEN],
+                start: usize,
+            ) -> ([u8; LEN], isize, isize) {
+                let v = DiyFp::from_bits(bits);
+                let (w_m, w_p) = v.normalized_boundaries();
+
+                let (c_mk, k) = get_cached_power(w_p.e);
+                let w = v.normalize().mul_round(c_mk);
+                let mut wp = w_p.mul_round(c_mk);
+                let mut wm = w_m.mul_round(c_mk);
+                wm.f += 1;
+                wp.f -= 1;
+                digit_gen(w, wp, wp.f - wm.f, buffer, start, k)
+            }
+
+            // Returns the output of `Buffer::format` and its length.
+            // `to_bits` is only const since Rust 1.83.
+            #[allow(unknown_lints, unnecessary_transmutes)]
+            pub const fn format(value: $fty) -> ([u8; LEN], usize) {
+                let bits = unsafe { mem::transmute::<$fty, $sigty>(value) };
+                let negative = bits & $sign_mask != 0;
+                if bits & $exponent_mask == $exponent_mask {
+                    return nonfinite(bits & $significand_mask != 0, negative);
+                }
+
+                let mut buffer = [0u8; LEN];
+                let start = negative as usize;
+                if negative {
+                    buffer[0] = b'-';
+                }
+                if bits & !$sign_mask == 0 {
+                    buffer[start] = b'0';
+                    prettify(buffer, start, 1, 0)
+                } else {
+                    let (buffer, length, k) = grisu2(bits, buffer, start);
+                    prettify(buffer, start, length, k)
+                }
+            }
+        }
+    };
+}
+
+const_dtoa! {
+    module: binary32,
+    grisu: grisu_f32,
+    floating_type: f32,
+    significand_type: u32,
+    exponent_type: i32,
+
+    sign_mask: 0x80000000,
+    exponent_mask: 0x7F800000,
+    significand_mask: 0x007FFFFF,
+}
+
+const_dtoa! {
+    module: binary64,
+    grisu: grisu_f64,
+    floating_type: f64,
+    significand_type: u64,
+    exponent_type: isize,
+
+    sign_mask: 0x8000000000000000,
+    exponent_mask: 0x7FF0000000000000,
+    significand_mask: 0x000FFFFFFFFFFFFF,
+}
+
+const fn nonfinite(nan: bool, negative: bool) -> ([u8; LEN], usize) {
+    let string = if nan {
+        crate::NAN
+    } else if negative {
+        crate::NEG_INFINITY
+    } else {
+        crate::INFINITY
+    };
+    let mut buffer = [0u8; LEN];
+    let mut i = 0;
+    while i < string.len() {
+        buffer[i] = string.as_bytes()[i];
+        i += 1;
+    }
+    (buffer, string.len())
+}
+
+const fn write_exponent(mut buffer: [u8; LEN], mut pos: usize, mut k: isize) -> ([u8; LEN], usize) {
+    if k < 0 {
+        buffer[pos] = b'-';
+        pos += 1;
+        k = -k;
+    }
+
+    if k >= 100 {
+        buffer[pos] = b'0' + (k / 100) as u8;
+        k %= 100;
+        buffer[pos + 1] = b'0' + (k / 10) as u8;
+        buffer[pos + 2] = b'0' + (k % 10) as u8;
+        (buffer, pos + 3)
+    } else if k >= 10 {
+        buffer[pos] = b'0' + (k / 10) as u8;
+        buffer[pos + 1] = b'0' + (k % 10) as u8;
+        (buffer, pos + 2)
+    } else {
+        buffer[pos] = b'0' + k as u8;
+        (buffer, pos + 1)
+    }
+}
+
+// Rearranges `length` digits at `start`, as `dtoa::prettify` does in place in
+// the same cases, and returns the end of the output. Truncation to
+// `MAX_DECIMAL_PLACES` never applies to Grisu digits.
+const fn prettify(
+    mut buffer: [u8; LEN],
+    start: usize,
+    length: isize,
+    k: isize,
+) -> ([u8; LEN], usize) {
+    let s = start as isize;
+    let kk = length + k; // 10^(kk-1) <= v < 10^kk
+
+    match dtoa::STANDARD.notation(length, k) {
+        Notation::Integer => {
+            // 1234e7 -> 12340000000.0
+            let mut i = length;
+            while i < kk {
+                buffer[(s + i) as usize] = b'0';
+                i += 1;
+            }
+            buffer[(s + kk) as usize] = b'.';
+            buffer[(s + kk + 1) as usize] = b'0';
+            (buffer, (s + kk + 2) as usize)
+        }
+        Notation::Decimal => {
+            // 1234e-2 -> 12.34
+            let mut i = length;
+            while i > kk {
+                buffer[(s + i) as usize] = buffer[(s + i - 1) as usize];
+                i -= 1;
+            }
+            buffer[(s + kk) as usize] = b'.';
+            (buffer, (s + length + 1) as usize)
+        }
+        Notation::Fraction => {
+            // 1234e-6 -> 0.001234
+            let offset = 2 - kk;
+            let mut i = length;
+            while i > 0 {
+                i -= 1;
+                buffer[(s + i + offset) as usize] = buffer[(s + i) as usize];
+            }
+            buffer[start] = b'0';
+            buffer[start + 1] = b'.';
+            let mut i = 2;
+            while i < offset {
+                buffer[(s + i) as usize] = b'0';
+                i += 1;
+            }
+            (buffer, (s + length + offset) as usize)
+        }
+        Notation::Zero => {
+            buffer[start] = b'0';
+            buffer[start + 1] = b'.';
+            buffer[start + 2] = b'0';
+            (buffer, start + 3)
+        }
+        Notation::Exponential if length == 1 => {
+            // 1e30
+            buffer[start + 1] = b'e';
+            write_exponent(buffer, start + 2, kk - 1)
+        }
+        Notation::Exponential => {
+            // 1234e30 -> 1.234e33
+            let mut i = length;
+            while i > 1 {
+                buffer[(s + i) as usize] = buffer[(s + i - 1) as usize];
+                i -= 1;
+            }
+            buffer[start + 1] = b'.';
+            buffer[(s + length + 1) as usize] = b'e';
+            write_exponent(buffer, (s + length + 2) as usize, kk - 1)
+        }
+    }
+}
@@ -37,8 +37,7 @@ pub struct DiyFp<F, E> {
 }
 
 impl<F, E> DiyFp<F, E> {
-    #[cfg_attr(feature = "no-panic", no_panic)]
-    pub fn new(f: F, e: E) -> Self {
+    pub const fn new(f: F, e: E) -> Self {
         DiyFp { f, e }
     }
 }
@@ -58,11 +57,9 @@ where
     }
 }
 
-impl Mul for DiyFp<u32, i32> {
-    type Output = Self;
-
-    #[cfg_attr(feature = "no-panic", no_panic)]
-    fn mul(self, rhs: Self) -> Self {
+// The `Mul` impls call these, which are also usable in const fn.
+impl DiyFp<u32, i32> {
+    pub const fn mul_round(self, rhs: Self) -> Self {
         let mut tmp = self.f as u64 * rhs.f as u64;
         tmp += 1u64 << 31; // mult_round
         DiyFp {
@@ -72,11 +69,8 @@ impl Mul for DiyFp<u32, i32> {
     }
 }
 
-impl Mul for DiyFp<u64, isize> {
-    type Output = Self;
-
-    #[cfg_attr(feature = "no-panic", no_panic)]
-    fn mul(self, rhs: Self) -> Self {
+impl DiyFp<u64, isize> {
+    pub const fn mul_round(self, rhs: Self) -> Self {
         let m32 = 0xFFFFFFFFu64;
         let a = self.f >> 32;
         let b = self.f & m32;
@@ -95,6 +89,24 @@ impl Mul for DiyFp<u64, isize> {
     }
 }
 
+impl Mul for DiyFp<u32, i32> {
+    type Output = Self;
+
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn mul(self, rhs: Self) -> Self {
+        self.mul_round(rhs)
+    }
+}
+
+impl Mul for DiyFp<u64, isize> {
+    type Output = Self;
+
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn mul(self, rhs: Self) -> Self {
+        self.mul_round(rhs)
+    }
+}
+
 macro_rules! diyfp {
     (
         floating_type: $fty:ty,
@@ -138,8 +150,11 @@ macro_rules! diyfp {
             */
             #[cfg_attr(feature = "no-panic", no_panic)]
             unsafe fn from(d: $fty) -> Self {
-                let u: $mask_type = <$fty>::to_bits(d);
+                DiyFp::from_bits(<$fty>::to_bits(d))
+            }
 
+            // Like `from`, for const fn which cannot call `to_bits`.
+            pub const fn from_bits(u: $mask_type) -> Self {
                 let biased_e = ((u & $exponent_mask) >> $significand_size) as $expty;
                 let significand = u & $significand_mask;
                 if biased_e != 0 {
@@ -166,8 +181,7 @@ macro_rules! diyfp {
                 return res;
             }
             */
-            #[cfg_attr(feature = "no-panic", no_panic)]
-            fn normalize(self) -> DiyFp {
+            pub const fn normalize(self) -> DiyFp {
                 let mut res = self;
                 while (res.f & (1 << ($diy_significand_size - 1))) == 0 {
                     res.f <<= 1;
@@ -192,8 +206,7 @@ macro_rules! diyfp {
                 return res;
             }
             */
-            #[cfg_attr(feature = "no-panic", no_panic)]
-            fn normalize_boundary(self) -> DiyFp {
+            const fn normalize_boundary(self) -> DiyFp {
                 let mut res = self;
                 while (res.f & $hidden_bit << 1) == 0 {
                     res.f <<= 1;
@@ -222,8 +235,7 @@ macro_rules! diyfp {
                 *minus = mi;
             }
             */
-            #[cfg_attr(feature = "no-panic", no_panic)]
-            fn normalized_boundaries(self) -> (DiyFp, DiyFp) {
+            pub const fn normalized_boundaries(self) -> (DiyFp, DiyFp) {
                 let pl = DiyFp::new((self.f << 1) + 1, self.e - 1).normalize_boundary();
                 let mut mi = if self.f == $hidden_bit {
                     DiyFp::new((self.f << 2) - 1, self.e - 2)
@@ -250,26 +262,26 @@ macro_rules! diyfp {
             return GetCachedPowerByIndex(index);
         }
         */
+        // Computes the ceiling with integers so that this is a const fn.
+        // 78913 / 2^18 approximates log10(2) closely enough that it agrees
+        // with the floating point computation for every exponent of a
+        // normalized boundary.
         #[inline]
-        #[cfg_attr(feature = "no-panic", no_panic)]
-        fn get_cached_power(e: $expty) -> (DiyFp, isize) {
-            let dk = (3 - $diy_significand_size - e) as f64 * 0.30102999566398114f64
-                - ($min_power + 1) as f64;
-            let mut k = dk as isize;
-            if dk - k as f64 > 0.0 {
-                k += 1;
-            }
+        pub const fn get_cached_power(e: $expty) -> (DiyFp, isize) {
+            let k = -((-(3 - $diy_significand_size - e) as isize * 78913) >> 18) - ($min_power + 1);
 
             let index = ((k >> 3) + 1) as usize;
             let k = -($min_power + (index << 3) as isize);
 
-            (
-                DiyFp::new(*unsafe { $cached_powers_f.get_unchecked(index) }, *unsafe {
-                    $cached_powers_e.get_unchecked(index)
-                }
-                    as $expty),
-                k,
-            )
+            unsafe {
+                (
+                    DiyFp::new(
+                        *$cached_powers_f.as_ptr().add(index),
+                        *$cached_powers_e.as_ptr().add(index) as $expty,
+                    ),
+                    k,
+                )
+            }
         }
     };
 }
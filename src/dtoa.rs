@@ -26,6 +26,7 @@
 // License for the specific language governing permissions and limitations under
 // the License.
 
+use crate::{layout, Float};
 use core::ptr;
 #[cfg(feature = "no-panic")]
 use no_panic::no_panic;
@@ -49,8 +50,7 @@ inline unsigned CountDecimalDigit32(uint32_t n) {
 */
 
 #[inline]
-#[cfg_attr(feature = "no-panic", no_panic)]
-pub fn count_decimal_digit32(n: u32) -> usize {
+pub const fn count_decimal_digit32(n: u32) -> usize {
     if n < 10 {
         1
     } else if n < 100 {
@@ -102,29 +102,54 @@ inline char* WriteExponent(int K, char* buffer) {
 
 #[inline]
 #[cfg_attr(feature = "no-panic", no_panic)]
-unsafe fn write_exponent(mut k: isize, mut buffer: *mut u8) -> *mut u8 {
+pub unsafe fn write_exponent<T: Copy + From<u8>>(mut k: isize, mut buffer: *mut T) -> *mut T {
     if k < 0 {
-        *buffer = b'-';
+        *buffer = T::from(b'-');
         buffer = buffer.add(1);
         k = -k;
     }
 
     if k >= 100 {
-        *buffer = b'0' + (k / 100) as u8;
+        *buffer = T::from(b'0' + (k / 100) as u8);
         k %= 100;
         let d = crate::DEC_DIGITS_LUT.as_ptr().offset(k * 2);
-        ptr::copy_nonoverlapping(d, buffer.add(1), 2);
+        *buffer.add(1) = T::from(*d);
+        *buffer.add(2) = T::from(*d.add(1));
         buffer.add(3)
     } else if k >= 10 {
         let d = crate::DEC_DIGITS_LUT.as_ptr().offset(k * 2);
-        ptr::copy_nonoverlapping(d, buffer, 2);
+        *buffer = T::from(*d);
+        *buffer.add(1) = T::from(*d.add(1));
         buffer.add(2)
     } else {
-        *buffer = b'0' + k as u8;
+        *buffer = T::from(b'0' + k as u8);
         buffer.add(1)
     }
 }
 
+// The choices `prettify` makes beyond those of the C++ implementation, so that
+// other output formats can reuse it. Positional notation is used if
+// `min_kk < kk <= max_kk`, otherwise exponential notation.
+#[derive(Copy, Clone)]
+pub struct Style {
+    pub min_kk: isize,
+    pub max_kk: isize,
+    // Whether integers in positional notation end in ".0", as in "1234.0".
+    pub point_zero: bool,
+    // Whether a single digit mantissa is followed by ".0", as in "1.0e30".
+    pub mantissa_point_zero: bool,
+    pub exponent_marker: u8,
+}
+
+// The style of `Buffer::format`.
+pub const STANDARD: Style = Style {
+    min_kk: -6,
+    max_kk: 21,
+    point_zero: true,
+    mantissa_point_zero: false,
+    exponent_marker: b'e',
+};
+
 /*
 inline char* Prettify(char* buffer, int length, int k, int maxDecimalPlaces) {
     const int kk = length + k;  // 10^(kk-1) <= v < 10^kk
@@ -132,8 +157,14 @@ inline char* Prettify(char* buffer, int length, int k, int maxDecimalPlaces) {
 
 #[inline]
 #[cfg_attr(feature = "no-panic", no_panic)]
-pub unsafe fn prettify(buffer: *mut u8, length: isize, k: isize) -> *mut u8 {
+pub unsafe fn prettify<T: Copy + PartialEq + From<u8>>(
+    buffer: *mut T,
+    length: isize,
+    k: isize,
+    style: Style,
+) -> *mut T {
     let kk = length + k; // 10^(kk-1) <= v < 10^kk
+    let notation = style.notation(length, k);
 
     /*
     if (0 <= k && kk <= 21) {
@@ -145,14 +176,18 @@ pub unsafe fn prettify(buffer: *mut u8, length: isize, k: isize) -> *mut u8 {
         return &buffer[kk + 2];
     }
     */
-    if 0 <= k && kk <= 21 {
+    if notation == Notation::Integer {
         // 1234e7 -> 12340000000
         for i in length..kk {
-            *buffer.offset(i) = b'0';
+            *buffer.offset(i) = T::from(b'0');
+        }
+        if style.point_zero {
+            *buffer.offset(kk) = T::from(b'.');
+            *buffer.offset(kk + 1) = T::from(b'0');
+            buffer.offset(kk + 2)
+        } else {
+            buffer.offset(kk)
         }
-        *buffer.offset(kk) = b'.';
-        *buffer.offset(kk + 1) = b'0';
-        buffer.offset(kk + 2)
     }
     /*
     else if (0 < kk && kk <= 21) {
@@ -171,19 +206,19 @@ pub unsafe fn prettify(buffer: *mut u8, length: isize, k: isize) -> *mut u8 {
             return &buffer[length + 1];
     }
     */
-    else if 0 < kk && kk <= 21 {
+    else if notation == Notation::Decimal {
         // 1234e-2 -> 12.34
         ptr::copy(
             buffer.offset(kk),
             buffer.offset(kk + 1),
             (length - kk) as usize,
         );
-        *buffer.offset(kk) = b'.';
+        *buffer.offset(kk) = T::from(b'.');
         if 0 > k + crate::MAX_DECIMAL_PLACES {
             // When MAX_DECIMAL_PLACES = 2, 1.2345 -> 1.23, 1.102 -> 1.1
             // Remove extra trailing zeros (at least one) after truncation.
             for i in (kk + 2..kk + crate::MAX_DECIMAL_PLACES + 1).rev() {
-                if *buffer.offset(i) != b'0' {
+                if *buffer.offset(i) != T::from(b'0') {
                     return buffer.offset(i + 1);
                 }
             }
@@ -213,20 +248,20 @@ pub unsafe fn prettify(buffer: *mut u8, length: isize, k: isize) -> *mut u8 {
             return &buffer[length + offset];
     }
     */
-    else if -6 < kk && kk <= 0 {
+    else if notation == Notation::Fraction {
         // 1234e-6 -> 0.001234
         let offset = 2 - kk;
         ptr::copy(buffer, buffer.offset(offset), length as usize);
-        *buffer = b'0';
-        *buffer.add(1) = b'.';
+        *buffer = T::from(b'0');
+        *buffer.add(1) = T::from(b'.');
         for i in 2..offset {
-            *buffer.offset(i) = b'0';
+            *buffer.offset(i) = T::from(b'0');
         }
         if length - kk > crate::MAX_DECIMAL_PLACES {
             // When MAX_DECIMAL_PLACES = 2, 0.123 -> 0.12, 0.102 -> 0.1
             // Remove extra trailing zeros (at least one) after truncation.
             for i in (3..crate::MAX_DECIMAL_PLACES + 2).rev() {
-                if *buffer.offset(i) != b'0' {
+                if *buffer.offset(i) != T::from(b'0') {
                     return buffer.offset(i + 1);
                 }
             }
@@ -244,10 +279,10 @@ pub unsafe fn prettify(buffer: *mut u8, length: isize, k: isize) -> *mut u8 {
         return &buffer[3];
     }
     */
-    else if kk < -crate::MAX_DECIMAL_PLACES {
-        *buffer = b'0';
-        *buffer.add(1) = b'.';
-        *buffer.add(2) = b'0';
+    else if notation == Notation::Zero {
+        *buffer = T::from(b'0');
+        *buffer.add(1) = T::from(b'.');
+        *buffer.add(2) = T::from(b'0');
         buffer.add(3)
     }
     /*
@@ -259,8 +294,14 @@ pub unsafe fn prettify(buffer: *mut u8, length: isize, k: isize) -> *mut u8 {
     */
     else if length == 1 {
         // 1e30
-        *buffer.add(1) = b'e';
-        write_exponent(kk - 1, buffer.add(2))
+        let mut buffer = buffer.add(1);
+        if style.mantissa_point_zero {
+            *buffer = T::from(b'.');
+            *buffer.add(1) = T::from(b'0');
+            buffer = buffer.add(2);
+        }
+        *buffer = T::from(style.exponent_marker);
+        write_exponent(kk - 1, buffer.add(1))
     }
     /*
     else {
@@ -274,12 +315,97 @@ pub unsafe fn prettify(buffer: *mut u8, length: isize, k: isize) -> *mut u8 {
     else {
         // 1234e30 -> 1.234e33
         ptr::copy(buffer.add(1), buffer.add(2), (length - 1) as usize);
-        *buffer.add(1) = b'.';
-        *buffer.offset(length + 1) = b'e';
+        *buffer.add(1) = T::from(b'.');
+        *buffer.offset(length + 1) = T::from(style.exponent_marker);
         write_exponent(kk - 1, buffer.offset(length + 2))
     }
 }
 
+// The cases of `prettify`, which the const fn version in const_dtoa.rs shares.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Notation {
+    // 1234e7 -> 12340000000
+    Integer,
+    // 1234e-2 -> 12.34
+    Decimal,
+    // 1234e-6 -> 0.001234
+    Fraction,
+    // Truncated to "0.0" by `MAX_DECIMAL_PLACES`.
+    Zero,
+    // 1e30, 1234e30 -> 1.234e33
+    Exponential,
+}
+
+impl Style {
+    // Whether `prettify` uses exponential notation for a value with
+    // 10^(kk-1) <= v < 10^kk.
+    #[inline]
+    pub const fn is_exponential(self, kk: isize) -> bool {
+        !(self.min_kk < kk && kk <= self.max_kk)
+    }
+
+    // Which case of `prettify` applies to `length` digits times 10^k.
+    #[inline]
+    pub const fn notation(self, length: isize, k: isize) -> Notation {
+        let kk = length + k;
+        if 0 <= k && kk <= self.max_kk {
+            Notation::Integer
+        } else if 0 < kk && kk <= self.max_kk {
+            Notation::Decimal
+        } else if self.min_kk < kk && kk <= 0 {
+            Notation::Fraction
+        } else if kk < -crate::MAX_DECIMAL_PLACES {
+            Notation::Zero
+        } else {
+            Notation::Exponential
+        }
+    }
+}
+
+// Length of the output of `prettify`, without sign. Its truncation to
+// `MAX_DECIMAL_PLACES` never applies to f32 or f64 digits.
+#[inline]
+#[cfg_attr(feature = "no-panic", no_panic)]
+pub fn prettified_len(length: isize, k: isize, style: Style) -> isize {
+    if style.is_exponential(length + k) {
+        exponential_len(length, k, style)
+    } else if k >= 0 && style.point_zero {
+        positional_len(length, k) + 2
+    } else {
+        positional_len(length, k)
+    }
+}
+
+// Length of positional notation without sign or ".0", like "1500", "1.5" or
+// "0.0015".
+#[inline]
+#[cfg_attr(feature = "no-panic", no_panic)]
+pub fn positional_len(length: isize, k: isize) -> isize {
+    let kk = length + k;
+    if k >= 0 {
+        kk
+    } else if kk > 0 {
+        length + 1
+    } else {
+        length + 2 - kk
+    }
+}
+
+// Length of exponential notation as written by `prettify`, without sign, like
+// "1e3" or "1.5e-3".
+#[inline]
+#[cfg_attr(feature = "no-panic", no_panic)]
+pub fn exponential_len(length: isize, k: isize, style: Style) -> isize {
+    let mantissa = if length > 1 {
+        length + 1
+    } else if style.mantissa_point_zero {
+        3
+    } else {
+        1
+    };
+    mantissa + layout::exponent_len(length + k - 1)
+}
+
 macro_rules! dtoa {
     (
         floating_type: $fty:ty,
@@ -292,7 +418,7 @@ macro_rules! dtoa {
             significand_type: $sigty,
             exponent_type: $expty,
             $($diyfp_param: $diyfp_value,)*
-        };
+        }
 
         /*
         inline void GrisuRound(char* buffer, int len, uint64_t delta, uint64_t rest, uint64_t ten_kappa, uint64_t wp_w) {
@@ -459,7 +585,7 @@ macro_rules! dtoa {
         // Returns length and k.
         #[inline]
         #[cfg_attr(feature = "no-panic", no_panic)]
-        unsafe fn grisu2(value: $fty, buffer: *mut u8) -> (isize, isize) {
+        pub unsafe fn grisu2(value: $fty, buffer: *mut u8) -> (isize, isize) {
             let v = DiyFp::from(value);
             let (w_m, w_p) = v.normalized_boundaries();
 
@@ -472,52 +598,181 @@ macro_rules! dtoa {
             digit_gen(w, wp, wp.f - wm.f, buffer, k)
         }
 
-        /*
-        inline char* dtoa(double value, char* buffer, int maxDecimalPlaces = 324) {
-            RAPIDJSON_ASSERT(maxDecimalPlaces >= 1);
-            Double d(value);
-            if (d.IsZero()) {
-                if (d.Sign())
-                    *buffer++ = '-';     // -0.0, Issue #289
-                buffer[0] = '0';
-                buffer[1] = '.';
-                buffer[2] = '0';
-                return &buffer[3];
+        // Grisu3 from Florian Loitsch's "Printing Floating-Point Numbers
+        // Quickly and Accurately with Integers", as in double-conversion's
+        // fast-dtoa.cc. Unlike Grisu2 it knows how imprecise the scaled
+        // boundaries are, and gives up instead of returning digits that are
+        // not provably the shortest and closest.
+
+        // Like grisu_round, moves the last digit down while that gets closer
+        // to w. Distances are measured down from too_high, and the one to w is
+        // only known to within a unit. Returns whether the digits are certainly
+        // inside the rounding interval and closest to w. If they are shorter
+        // than min_length, also requires that appending zeros gives the closest
+        // candidate with min_length digits.
+        #[inline]
+        #[allow(clippy::too_many_arguments)]
+        #[cfg_attr(feature = "no-panic", no_panic)]
+        unsafe fn round_weed(
+            buffer: *mut u8,
+            len: isize,
+            min_length: isize,
+            distance_too_high_w: $sigty,
+            unsafe_interval: $sigty,
+            mut rest: $sigty,
+            ten_kappa: $sigty,
+            unit: $sigty,
+        ) -> bool {
+            let small_distance = distance_too_high_w - unit;
+            let big_distance = distance_too_high_w + unit;
+            while rest < small_distance && unsafe_interval - rest >= ten_kappa &&
+                (rest + ten_kappa < small_distance || // closer
+                    small_distance - rest >= rest + ten_kappa - small_distance) {
+                *buffer.offset(len - 1) -= 1;
+                rest += ten_kappa;
             }
-            else {
-                if (value < 0) {
-                    *buffer++ = '-';
-                    value = -value;
-                }
-                int length, K;
-                Grisu2(value, buffer, &length, &K);
-                return Prettify(buffer, length, K, maxDecimalPlaces);
+
+            // The next smaller candidate might be closer to the real w.
+            if rest < big_distance && unsafe_interval - rest >= ten_kappa &&
+                (rest + ten_kappa < big_distance ||
+                    big_distance - rest > rest + ten_kappa - big_distance) {
+                return false;
+            }
+
+            // Stay a few units away from the boundaries, which are not exact
+            // either.
+            if rest < 2 * unit || unsafe_interval - rest < 4 * unit {
+                return false;
+            }
+
+            if len >= min_length {
+                return true;
+            }
+            let mut half_spacing = ten_kappa / 2;
+            for _ in len..min_length {
+                half_spacing /= 10;
+            }
+            // Below a power of 10 the candidates are 10 times closer together.
+            if len == 1 && *buffer == b'1' {
+                half_spacing /= 10;
             }
+            distance_too_high_w.abs_diff(rest) + unit < half_spacing
         }
-        */
 
+        // Returns length and k, or None if the digits are uncertain.
         #[inline]
         #[cfg_attr(feature = "no-panic", no_panic)]
-        unsafe fn dtoa(buf: &mut Buffer, mut value: $fty) -> &str {
-            if value == 0.0 {
-                if value.is_sign_negative() {
-                    "-0.0"
-                } else {
-                    "0.0"
+        unsafe fn digit_gen3(low: DiyFp, w: DiyFp, high: DiyFp, buffer: *mut u8, min_length: isize, mut k: isize) -> Option<(isize, isize)> {
+            static POW10: [$sigty; 10] = [ 1, 10, 100, 1000, 10000, 100000, 1000000, 10000000, 100000000, 1000000000 ];
+            let mut unit = 1;
+            let too_low = DiyFp::new(low.f - unit, low.e);
+            let too_high = DiyFp::new(high.f + unit, high.e);
+            let mut unsafe_interval = too_high - too_low;
+            let one = DiyFp::new(1 << -w.e, w.e);
+            let mut p1 = (too_high.f >> -one.e) as u32;
+            let mut p2 = too_high.f & (one.f - 1);
+            let mut kappa = dtoa::count_decimal_digit32(p1); // kappa in [0, 9]
+            let mut len = 0;
+
+            while kappa > 0 {
+                // Constant divisors as in digit_gen, which cannot panic.
+                let mut d = 0u32;
+                match kappa {
+                    9 => { d = p1 /  100000000; p1 %=  100000000; }
+                    8 => { d = p1 /   10000000; p1 %=   10000000; }
+                    7 => { d = p1 /    1000000; p1 %=    1000000; }
+                    6 => { d = p1 /     100000; p1 %=     100000; }
+                    5 => { d = p1 /      10000; p1 %=      10000; }
+                    4 => { d = p1 /       1000; p1 %=       1000; }
+                    3 => { d = p1 /        100; p1 %=        100; }
+                    2 => { d = p1 /         10; p1 %=         10; }
+                    1 => { d = p1;              p1 =           0; }
+                    _ => {}
+                }
+                if d != 0 || len != 0 {
+                    *buffer.offset(len) = b'0' + d as u8;
+                    len += 1;
                 }
-            } else {
-                let start = buf.bytes.as_mut_ptr().cast::<u8>();
-                let mut buf_ptr = start;
-                if value < 0.0 {
-                    *buf_ptr = b'-';
-                    buf_ptr = buf_ptr.add(1);
-                    value = -value;
+                kappa -= 1;
+                let rest = ((p1 as $sigty) << -one.e) + p2;
+                if rest < unsafe_interval.f {
+                    k += kappa as isize;
+                    let ten_kappa = *POW10.get_unchecked(kappa) << -one.e;
+                    let distance = (too_high - w).f;
+                    let certain = round_weed(buffer, len, min_length, distance, unsafe_interval.f, rest, ten_kappa, unit);
+                    return if certain { Some((len, k)) } else { None };
+                }
+            }
+
+            // kappa = 0
+            loop {
+                p2 *= 10;
+                unit *= 10;
+                unsafe_interval.f *= 10;
+                let d = (p2 >> -one.e) as u8;
+                if d != 0 || len != 0 {
+                    *buffer.offset(len) = b'0' + d;
+                    len += 1;
+                }
+                p2 &= one.f - 1;
+                kappa = kappa.wrapping_sub(1);
+                if p2 < unsafe_interval.f {
+                    k += kappa as isize;
+                    let distance = (too_high - w).f * unit;
+                    let certain = round_weed(buffer, len, min_length, distance, unsafe_interval.f, p2, one.f, unit);
+                    return if certain { Some((len, k)) } else { None };
                 }
-                let (length, k) = grisu2(value, buf_ptr);
-                let end = dtoa::prettify(buf_ptr, length, k);
-                let len = end as usize - start as usize;
-                str::from_utf8_unchecked(slice::from_raw_parts(start, len))
             }
         }
+
+        // Returns length and k, or None if the digits are uncertain.
+        #[inline]
+        #[cfg_attr(feature = "no-panic", no_panic)]
+        pub unsafe fn grisu3(value: $fty, min_length: isize, buffer: *mut u8) -> Option<(isize, isize)> {
+            let v = DiyFp::from(value);
+            let (w_m, w_p) = v.normalized_boundaries();
+
+            let (c_mk, k) = get_cached_power(w_p.e);
+            let w = v.normalize() * c_mk;
+            let wp = w_p * c_mk;
+            let wm = w_m * c_mk;
+            digit_gen3(wm, w, wp, buffer, min_length, k)
+        }
     };
 }
+
+/*
+inline char* dtoa(double value, char* buffer, int maxDecimalPlaces = 324) {
+    RAPIDJSON_ASSERT(maxDecimalPlaces >= 1);
+    Double d(value);
+    if (d.IsZero()) {
+        if (d.Sign())
+            *buffer++ = '-';     // -0.0, Issue #289
+        buffer[0] = '0';
+        buffer[1] = '.';
+        buffer[2] = '0';
+        return &buffer[3];
+    }
+    else {
+        if (value < 0) {
+            *buffer++ = '-';
+            value = -value;
+        }
+        int length, K;
+        Grisu2(value, buffer, &length, &K);
+        return Prettify(buffer, length, K, maxDecimalPlaces);
+    }
+}
+*/
+
+#[inline]
+#[cfg_attr(feature = "no-panic", no_panic)]
+pub unsafe fn dtoa<F: Float>(value: F, buffer: *mut u8) -> *mut u8 {
+    let mut buffer = buffer;
+    if value.is_sign_negative() {
+        *buffer = b'-';
+        buffer = buffer.add(1);
+    }
+    let (length, k) = value.grisu(buffer);
+    prettify(buffer, length, k, STANDARD)
+}
@@ -0,0 +1,159 @@
+use crate::exact::{self, Rounding};
+use crate::{format_nonfinite, layout, write_signed, Float, LargeBuffer};
+use core::str;
+
+const NAN: &str = "NaN";
+const INFINITY: &str = "Infinity";
+const NEG_INFINITY: &str = "-Infinity";
+
+impl LargeBuffer {
+    /// Print a floating point number with a fixed number of digits after the
+    /// decimal point, following JavaScript's `Number.prototype.toFixed`.
+    ///
+    /// Ties are rounded away from zero based on the exact value of the float,
+    /// so `to_fixed(1.005, 2)` is "1.00" because the float closest to 1.005 is
+    /// slightly less than it. Values whose magnitude is at least 10<sup>21</sup>
+    /// are printed in exponential notation as by JavaScript's `toString`.
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "NaN", positive infinity as "Infinity", and negative
+    /// infinity as "-Infinity". Negative zero is formatted without a sign.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `digits` is greater than 100, where JavaScript would throw a
+    /// RangeError.
+    pub fn to_fixed<F: Float>(&mut self, value: F, digits: usize) -> &str {
+        assert!(
+            digits <= 100,
+            "toFixed() digits argument must be between 0 and 100",
+        );
+        if value.is_nonfinite() {
+            return format_nonfinite(value, NAN, INFINITY, NEG_INFINITY);
+        }
+
+        let mut exact_digits = [0u8; exact::MAX_DIGITS];
+        let (f, e) = value.decode();
+        let (length, k) = exact::exact(f, e, &mut exact_digits);
+        let negative = value.is_sign_negative() && f != 0;
+        unsafe {
+            write_signed(&mut self.bytes, negative, |buffer| {
+                if length + k > 21 {
+                    // Same as Number.prototype.toString.
+                    let mut digits = [0u8; exact::SHORTEST_DIGITS];
+                    let (length, k) = exact::shortest(value, &mut digits);
+                    write_exponential(buffer, &digits, length, k, length)
+                } else {
+                    let keep = length + k + digits as isize;
+                    let (length, k) =
+                        exact::round(&mut exact_digits, length, k, keep, Rounding::HalfUp);
+                    layout::write_fixed(buffer, &exact_digits, length, k, digits as isize)
+                }
+            })
+        }
+    }
+
+    /// Print a floating point number in exponential notation with a fixed
+    /// number of digits after the decimal point, following JavaScript's
+    /// `Number.prototype.toExponential`.
+    ///
+    /// Ties are rounded away from zero based on the exact value of the float.
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "NaN", positive infinity as "Infinity", and negative
+    /// infinity as "-Infinity". Negative zero is formatted without a sign.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `digits` is greater than 100 and the value is finite, where
+    /// JavaScript would throw a RangeError.
+    pub fn to_exponential<F: Float>(&mut self, value: F, digits: usize) -> &str {
+        if value.is_nonfinite() {
+            return format_nonfinite(value, NAN, INFINITY, NEG_INFINITY);
+        }
+        assert!(
+            digits <= 100,
+            "toExponential() argument must be between 0 and 100",
+        );
+
+        let mut exact_digits = [0u8; exact::MAX_DIGITS];
+        let (length, k, negative) =
+            round_significant(value, digits as isize + 1, &mut exact_digits);
+        unsafe {
+            write_signed(&mut self.bytes, negative, |buffer| {
+                write_exponential(buffer, &exact_digits, length, k, digits as isize + 1)
+            })
+        }
+    }
+
+    /// Print a floating point number with a fixed number of significant
+    /// digits, following JavaScript's `Number.prototype.toPrecision`.
+    ///
+    /// Exponential notation is used if the exponent is less than -6 or at least
+    /// `precision`. Ties are rounded away from zero based on the exact value of
+    /// the float.
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "NaN", positive infinity as "Infinity", and negative
+    /// infinity as "-Infinity". Negative zero is formatted without a sign.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not between 1 and 100 and the value is finite,
+    /// where JavaScript would throw a RangeError.
+    pub fn to_precision<F: Float>(&mut self, value: F, precision: usize) -> &str {
+        if value.is_nonfinite() {
+            return format_nonfinite(value, NAN, INFINITY, NEG_INFINITY);
+        }
+        assert!(
+            (1..=100).contains(&precision),
+            "toPrecision() argument must be between 1 and 100",
+        );
+
+        let mut exact_digits = [0u8; exact::MAX_DIGITS];
+        let precision = precision as isize;
+        let (length, k, negative) = round_significant(value, precision, &mut exact_digits);
+        unsafe {
+            write_signed(&mut self.bytes, negative, |buffer| {
+                let e = length + k - 1;
+                if e < -6 || e >= precision {
+                    write_exponential(buffer, &exact_digits, length, k, precision)
+                } else {
+                    layout::write_fixed(buffer, &exact_digits, length, k, precision - 1 - e)
+                }
+            })
+        }
+    }
+}
+
+// Returns the exact digits rounded to `precision` significant digits, and
+// whether to print a minus sign. Zero is the single digit "0".
+fn round_significant<F: Float>(
+    value: F,
+    precision: isize,
+    digits: &mut [u8; exact::MAX_DIGITS],
+) -> (isize, isize, bool) {
+    let (f, e) = value.decode();
+    let (length, k) = exact::exact(f, e, digits);
+    if f == 0 {
+        return (length, k, false);
+    }
+    let (length, k) = exact::round(digits, length, k, precision, Rounding::HalfUp);
+    (length, k, value.is_sign_negative())
+}
+
+// Writes exactly `precision` significant digits in exponential notation, like
+// "1.2300e+5".
+unsafe fn write_exponential(
+    buffer: *mut u8,
+    digits: &[u8],
+    length: isize,
+    k: isize,
+    precision: isize,
+) -> *mut u8 {
+    let buffer = layout::write_mantissa(buffer, digits, length, precision);
+    layout::write_exponent(buffer, b'e', length + k - 1, true, false)
+}
@@ -0,0 +1,346 @@
+// Exact decimal expansion of a float, used by the formatting modes that need a
+// fixed number of correctly rounded digits rather than the shortest digits
+// produced by Grisu.
+//
+// A finite float is f × 2^e. For e >= 0 that is the integer f × 2^e, and for
+// e < 0 it is the integer f × 5^-e scaled by 10^e. Either way the digits are
+// those of an integer of at most 2547 bits, which is formatted by repeated
+// division by 10^9.
+
+use crate::Float;
+use core::cmp::Ordering;
+
+// Digits in the longest exact expansion needed, which is that of the upper
+// boundary of the largest f64 with the smallest exponent: ceil(log10(2^54 ×
+// 5^1075)). The float itself has at most 767 digits.
+pub const MAX_DIGITS: usize = 768;
+
+const LIMBS: usize = 80;
+
+struct Bignum {
+    limbs: [u32; LIMBS],
+    len: usize,
+}
+
+impl Bignum {
+    fn from_u64(n: u64) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = n as u32;
+        limbs[1] = (n >> 32) as u32;
+        let mut bignum = Bignum { limbs, len: 2 };
+        bignum.trim();
+        bignum
+    }
+
+    fn mul_small(&mut self, m: u32) {
+        let mut carry = 0u64;
+        for limb in &mut self.limbs[..self.len] {
+            let product = *limb as u64 * m as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry != 0 {
+            self.limbs[self.len] = carry as u32;
+            self.len += 1;
+        }
+    }
+
+    fn mul_pow5(&mut self, mut n: usize) {
+        // 5^13 is the largest power of 5 that fits in u32.
+        while n >= 13 {
+            self.mul_small(1220703125);
+            n -= 13;
+        }
+        self.mul_small(5u32.pow(n as u32));
+    }
+
+    fn mul_pow2(&mut self, n: usize) {
+        let limbs = n / 32;
+        let bits = n % 32;
+        if self.len == 0 {
+            return;
+        }
+        if bits != 0 {
+            let mut carry = 0;
+            for limb in &mut self.limbs[..self.len] {
+                let shifted = *limb << bits | carry;
+                carry = *limb >> (32 - bits);
+                *limb = shifted;
+            }
+            if carry != 0 {
+                self.limbs[self.len] = carry;
+                self.len += 1;
+            }
+        }
+        if limbs != 0 {
+            self.limbs.copy_within(..self.len, limbs);
+            self.limbs[..limbs].fill(0);
+            self.len += limbs;
+        }
+    }
+
+    // Divides in place and returns the remainder.
+    fn div_small(&mut self, d: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs[..self.len].iter_mut().rev() {
+            let n = rem << 32 | *limb as u64;
+            *limb = (n / d as u64) as u32;
+            rem = n % d as u64;
+        }
+        self.trim();
+        rem as u32
+    }
+
+    fn trim(&mut self) {
+        while self.len > 0 && self.limbs[self.len - 1] == 0 {
+            self.len -= 1;
+        }
+    }
+}
+
+// Writes every digit of the exact decimal value of f × 2^e, without leading or
+// trailing zeros, and returns length and k as for Grisu. Zero is written as the
+// single digit "0".
+pub fn exact(f: u64, e: isize, digits: &mut [u8; MAX_DIGITS]) -> (isize, isize) {
+    if f == 0 {
+        digits[0] = b'0';
+        return (1, 0);
+    }
+
+    let mut n = Bignum::from_u64(f);
+    let mut k = 0;
+    if e >= 0 {
+        n.mul_pow2(e as usize);
+    } else {
+        n.mul_pow5(-e as usize);
+        k = e;
+    }
+
+    // Produce groups of 9 digits from least significant to most significant,
+    // filling the array from the back.
+    let mut pos = MAX_DIGITS;
+    while n.len > 0 {
+        let mut group = n.div_small(1_000_000_000);
+        let stop = if n.len == 0 { 0 } else { pos.saturating_sub(9) };
+        while pos > stop && (group != 0 || n.len > 0) {
+            pos -= 1;
+            digits[pos] = b'0' + (group % 10) as u8;
+            group /= 10;
+        }
+    }
+
+    let mut end = MAX_DIGITS;
+    while digits[end - 1] == b'0' {
+        end -= 1;
+        k += 1;
+    }
+    digits.copy_within(pos..end, 0);
+    ((end - pos) as isize, k)
+}
+
+// Digits in the longest result of `shortest`, which is that of an f64.
+pub const SHORTEST_DIGITS: usize = 17;
+
+// Writes the shortest digits of the absolute value, which must be finite, that
+// round to the same float when parsed, choosing the closest if there are
+// several, and returns length and k. Unlike Grisu2 this is exact: Grisu3
+// handles most values, and the rest are worked out with bignums.
+pub fn shortest<F: Float>(value: F, digits: &mut [u8; SHORTEST_DIGITS]) -> (isize, isize) {
+    shortest_at_least(value, 1, digits)
+}
+
+// Like `shortest` but considers candidates of at least `min_length` digits,
+// so a closer candidate of `min_length` digits is preferred over a shorter one.
+// `min_length` must be between 1 and `SHORTEST_DIGITS`.
+pub fn shortest_at_least<F: Float>(
+    value: F,
+    min_length: isize,
+    digits: &mut [u8; SHORTEST_DIGITS],
+) -> (isize, isize) {
+    shortest_in(value, min_length, true, digits)
+}
+
+// Like `shortest` but never chooses a boundary of the rounding interval, even
+// when it parses to the value because the significand is even. PostgreSQL's
+// copy of Ryu works this way.
+pub fn shortest_inside<F: Float>(value: F, digits: &mut [u8; SHORTEST_DIGITS]) -> (isize, isize) {
+    shortest_in(value, 1, false, digits)
+}
+
+fn shortest_in<F: Float>(
+    value: F,
+    min_length: isize,
+    boundaries: bool,
+    digits: &mut [u8; SHORTEST_DIGITS],
+) -> (isize, isize) {
+    if value.is_zero() {
+        digits[0] = b'0';
+        return (1, 0);
+    }
+    // Grisu3 gives up near either boundary, so only the bignums need to know
+    // whether they are allowed.
+    match unsafe { value.grisu3(min_length, digits.as_mut_ptr()) } {
+        Some(result) => result,
+        None => shortest_bignum(value, min_length, boundaries, digits),
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn shortest_bignum<F: Float>(
+    value: F,
+    min_length: isize,
+    boundaries: bool,
+    digits: &mut [u8; SHORTEST_DIGITS],
+) -> (isize, isize) {
+    let (f, e) = value.decode();
+    let mut exact_digits = [0u8; MAX_DIGITS];
+    let (length, k) = exact(f, e, &mut exact_digits);
+
+    let mut lower_digits = [0u8; MAX_DIGITS];
+    let mut upper_digits = [0u8; MAX_DIGITS];
+    let (lower_length, lower_k) = if value.is_lower_boundary_closer() {
+        exact(4 * f - 1, e - 2, &mut lower_digits)
+    } else {
+        exact(2 * f - 1, e - 1, &mut lower_digits)
+    };
+    let (upper_length, upper_k) = exact(2 * f + 1, e - 1, &mut upper_digits);
+    let lower = (&lower_digits[..], lower_length, lower_k);
+    let upper = (&upper_digits[..], upper_length, upper_k);
+    // Round-half-even parsing maps a boundary to the float with even
+    // significand.
+    let inclusive = boundaries && f % 2 == 0;
+    let in_interval = |candidate: (&[u8], isize, isize)| {
+        let above_lower = compare(candidate, lower);
+        let below_upper = compare(upper, candidate);
+        if inclusive {
+            above_lower != Ordering::Less && below_upper != Ordering::Less
+        } else {
+            above_lower == Ordering::Greater && below_upper == Ordering::Greater
+        }
+    };
+
+    // Rounding to SHORTEST_DIGITS digits always lands in the interval, so no
+    // longer candidates are needed.
+    let max_length = SHORTEST_DIGITS as isize;
+    let mut candidate = [0u8; SHORTEST_DIGITS];
+    for keep in min_length..length.min(max_length) {
+        let n = keep as usize;
+        candidate[..=n].copy_from_slice(&exact_digits[..=n]);
+        let (len, kc) = round(&mut candidate, length, k, keep, Rounding::HalfEven);
+        if in_interval((&candidate, len, kc)) {
+            digits[..len as usize].copy_from_slice(&candidate[..len as usize]);
+            return (len, kc);
+        }
+        // The closest candidate with this many digits may fall outside an
+        // asymmetric interval while the one on the other side is inside.
+        let rounding = if compare((&candidate, len, kc), lower) == Ordering::Greater {
+            Rounding::Down
+        } else {
+            Rounding::Up
+        };
+        candidate[..=n].copy_from_slice(&exact_digits[..=n]);
+        let (len, kc) = round(&mut candidate, length, k, keep, rounding);
+        if in_interval((&candidate, len, kc)) {
+            digits[..len as usize].copy_from_slice(&candidate[..len as usize]);
+            return (len, kc);
+        }
+    }
+    let (len, kc) = round(&mut exact_digits, length, k, max_length, Rounding::HalfEven);
+    digits[..len as usize].copy_from_slice(&exact_digits[..len as usize]);
+    (len, kc)
+}
+
+// Compares two positive nonzero values given as digits, length and k.
+fn compare(a: (&[u8], isize, isize), b: (&[u8], isize, isize)) -> Ordering {
+    let (a_digits, a_length, a_k) = a;
+    let (b_digits, b_length, b_k) = b;
+    let ordering = (a_length + a_k).cmp(&(b_length + b_k));
+    if ordering != Ordering::Equal {
+        return ordering;
+    }
+    for i in 0..a_length.max(b_length) as usize {
+        let a_digit = a_digits[..a_length as usize].get(i).unwrap_or(&b'0');
+        let b_digit = b_digits[..b_length as usize].get(i).unwrap_or(&b'0');
+        let ordering = a_digit.cmp(b_digit);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Rounding {
+    // Ties round to the candidate with an even last digit, as in IEEE 754.
+    HalfEven,
+    // Ties round away from zero.
+    HalfUp,
+    // Toward zero.
+    Down,
+    // Away from zero.
+    Up,
+}
+
+// Rounds the digits produced by `exact` to the first `keep` digits, where
+// `keep` may be zero or negative if the rounding position lies before the first
+// digit. Returns the new length and k, with trailing zeros removed. A length of
+// 0 means the value rounded to zero.
+pub fn round(
+    digits: &mut [u8],
+    length: isize,
+    k: isize,
+    keep: isize,
+    rounding: Rounding,
+) -> (isize, isize) {
+    if keep >= length {
+        return (length, k);
+    }
+    if keep < 0 {
+        if rounding == Rounding::Up {
+            digits[0] = b'1';
+            return (1, k + length - keep);
+        }
+        return (0, k);
+    }
+
+    let keep_usize = keep as usize;
+    let next = digits[keep_usize];
+    let round_up = match rounding {
+        Rounding::HalfEven => {
+            next > b'5'
+                || next == b'5'
+                    && (length > keep + 1 || keep > 0 && (digits[keep_usize - 1] - b'0') % 2 == 1)
+        }
+        Rounding::HalfUp => next >= b'5',
+        Rounding::Down => false,
+        // Trailing zeros were removed, so something nonzero is discarded.
+        Rounding::Up => true,
+    };
+    let k = k + length - keep;
+
+    let mut len = keep_usize;
+    if round_up {
+        loop {
+            if len == 0 {
+                // All nines, or nothing kept: carry into a new leading digit.
+                digits[0] = b'1';
+                return (1, k + keep);
+            }
+            if digits[len - 1] == b'9' {
+                len -= 1;
+            } else {
+                digits[len - 1] += 1;
+                break;
+            }
+        }
+    } else {
+        while len > 0 && digits[len - 1] == b'0' {
+            len -= 1;
+        }
+        if len == 0 {
+            return (0, k);
+        }
+    }
+    (len as isize, k + (keep - len as isize))
+}
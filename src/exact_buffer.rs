@@ -0,0 +1,100 @@
+use crate::{exact, layout, write_signed, Float};
+use core::mem::MaybeUninit;
+use core::str;
+
+/// A stack allocation large enough for the exact decimal value of any float,
+/// for use with [`format_exact`][ExactBuffer::format_exact].
+///
+/// This is much larger than [`Buffer`][crate::Buffer] because every binary
+/// fraction has a terminating decimal expansion, and for the smallest
+/// subnormal `f64` that expansion runs to 1074 decimal places.
+///
+/// # Example
+///
+/// ```
+/// let mut buffer = dtoa::ExactBuffer::new();
+/// let printed = buffer.format_exact(0.1);
+/// assert_eq!(printed, "0.1000000000000000055511151231257827021181583404541015625");
+/// ```
+pub struct ExactBuffer {
+    bytes: [MaybeUninit<u8>; EXACT_BUFFER_LEN],
+}
+
+// The longest output is that of the negative subnormal f64 values with an odd
+// significand, whose last digit is at 10^-1074: "-0." followed by 1074 digits.
+const EXACT_BUFFER_LEN: usize = 1077;
+
+impl Default for ExactBuffer {
+    #[inline]
+    fn default() -> ExactBuffer {
+        ExactBuffer::new()
+    }
+}
+
+impl Copy for ExactBuffer {}
+
+#[allow(clippy::non_canonical_clone_impl)]
+impl Clone for ExactBuffer {
+    #[inline]
+    fn clone(&self) -> Self {
+        ExactBuffer::new()
+    }
+}
+
+impl ExactBuffer {
+    /// This is a cheap operation; you don't need to worry about reusing buffers
+    /// for efficiency.
+    #[inline]
+    pub fn new() -> ExactBuffer {
+        let bytes = [MaybeUninit::<u8>::uninit(); EXACT_BUFFER_LEN];
+        ExactBuffer { bytes }
+    }
+
+    /// Print the exact decimal value of a floating point number into this
+    /// buffer and return a reference to its string representation within the
+    /// buffer.
+    ///
+    /// Every digit of the value is printed in positional notation, without
+    /// rounding, so the output parses back to the same float and shows how far
+    /// it is from the decimal number it was written as. This takes up to 767
+    /// significant digits for an `f64` and 112 for an `f32`. As with
+    /// [`Buffer::format`][crate::Buffer::format], an integer is printed with a
+    /// trailing ".0".
+    ///
+    /// # Special cases
+    ///
+    /// This function formats NaN as the string "NaN", positive infinity as
+    /// "inf", and negative infinity as "-inf" to match std::fmt.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::ExactBuffer::new();
+    /// assert_eq!(buffer.format_exact(0.1f32), "0.100000001490116119384765625");
+    /// assert_eq!(buffer.format_exact(1e23), "99999999999999991611392.0");
+    /// assert_eq!(buffer.format_exact(-0.0), "-0.0");
+    /// ```
+    pub fn format_exact<F: Float>(&mut self, value: F) -> &str {
+        if value.is_nonfinite() {
+            return value.format_nonfinite();
+        }
+
+        let mut digits = [0u8; exact::MAX_DIGITS];
+        let (f, e) = value.decode();
+        let (length, k) = exact::exact(f, e, &mut digits);
+
+        unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                let fraction = (-k).max(0);
+                let end = layout::write_fixed(buffer, &digits, length, k, fraction);
+                if fraction == 0 {
+                    *end = b'.';
+                    *end.add(1) = b'0';
+                    end.add(2)
+                } else {
+                    end
+                }
+            })
+        }
+    }
+}
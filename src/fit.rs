@@ -0,0 +1,89 @@
+use crate::exact::{self, Rounding};
+use crate::{dtoa, layout, write_signed, Float, LargeBuffer};
+use core::str;
+
+impl LargeBuffer {
+    /// Print a floating point number as precisely as possible within `width`
+    /// characters, for fixed-width columns such as in terminal tables.
+    ///
+    /// Starting from the shortest digits that round trip, each number of
+    /// significant digits is tried from most to fewest, first in positional
+    /// form ("1234.5", "0.00012") and then in scientific form ("1.2345e3",
+    /// "1.2e-4"). The first candidate that fits is returned. Digits are rounded
+    /// from the exact value of the float with ties to even, so there is no
+    /// double rounding. Integers are printed without a decimal point.
+    ///
+    /// The result is not padded; right-align it with `{:>width$}`.
+    ///
+    /// Returns `None` if not even a single significant digit fits. NaN and
+    /// infinities are printed as "NaN", "inf" and "-inf" if they fit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::LargeBuffer::new();
+    /// assert_eq!(buffer.format_fit(3.14159265, 6), Some("3.1416"));
+    /// assert_eq!(buffer.format_fit(-123456789.0, 8), Some("-1.235e8"));
+    /// assert_eq!(buffer.format_fit(0.000123456, 6), Some("1.2e-4"));
+    /// assert_eq!(buffer.format_fit(1e-300, 4), None);
+    ///
+    /// let cell = format!("{:>8}", buffer.format_fit(2.5, 8).unwrap());
+    /// assert_eq!(cell, "     2.5");
+    /// ```
+    pub fn format_fit<F: Float>(&mut self, value: F, width: usize) -> Option<&str> {
+        if value.is_nonfinite() {
+            let printed = value.format_nonfinite();
+            return if printed.len() <= width {
+                Some(printed)
+            } else {
+                None
+            };
+        }
+
+        let sign = value.is_sign_negative() as usize;
+        let width = width.checked_sub(sign)? as isize;
+        let mut shortest = [0u8; exact::SHORTEST_DIGITS];
+        let (shortest_length, shortest_k) = exact::shortest(value, &mut shortest);
+
+        let mut exact = [0u8; exact::MAX_DIGITS];
+        let mut exact_length = 0;
+        let mut exact_k = 0;
+        let mut rounded = [0u8; exact::MAX_DIGITS];
+        for precision in (1..=shortest_length).rev() {
+            let (digits, length, k) = if precision == shortest_length {
+                (&shortest[..], shortest_length, shortest_k)
+            } else {
+                if exact_length == 0 {
+                    let (f, e) = value.decode();
+                    (exact_length, exact_k) = exact::exact(f, e, &mut exact);
+                }
+                rounded[..exact_length as usize].copy_from_slice(&exact[..exact_length as usize]);
+                let (length, k) = exact::round(
+                    &mut rounded,
+                    exact_length,
+                    exact_k,
+                    precision,
+                    Rounding::HalfEven,
+                );
+                (&rounded[..], length, k)
+            };
+
+            // No candidate is longer than the positional form of the shortest
+            // digits, which the buffer is sized for.
+            let positional = dtoa::positional_len(length, k) <= width;
+            if positional || dtoa::exponential_len(length, k, dtoa::STANDARD) <= width {
+                return Some(unsafe {
+                    write_signed(&mut self.bytes, sign == 1, |buffer| {
+                        if positional {
+                            layout::write_fixed(buffer, digits, length, k, (-k).max(0))
+                        } else {
+                            let buffer = layout::write_mantissa(buffer, digits, length, length);
+                            layout::write_exponent(buffer, b'e', length + k - 1, false, false)
+                        }
+                    })
+                });
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{const_dtoa, slice_output, Float};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use core::{slice, str};
+
+/// A formatted floating point number stored inline, returned by value from
+/// [`to_string_inline`].
+///
+/// Unlike the `&str` returned by [`Buffer::format`][crate::Buffer::format],
+/// this does not borrow from anything, so it can be stored in structs and
+/// returned from functions. It dereferences to `str` and compares, orders
+/// and hashes the same as its string.
+#[derive(Copy, Clone)]
+pub struct Formatted {
+    bytes: [u8; const_dtoa::LEN],
+    len: u8,
+}
+
+/// Print a floating point number into an owned [`Formatted`] value, with the
+/// same output as [`Buffer::format`][crate::Buffer::format].
+///
+/// # Example
+///
+/// ```
+/// let formatted = dtoa::to_string_inline(2.71828f64);
+/// assert_eq!(&*formatted, "2.71828");
+/// assert_eq!(formatted.len(), 7);
+/// ```
+pub fn to_string_inline<F: Float>(value: F) -> Formatted {
+    let mut bytes = [0u8; const_dtoa::LEN];
+    let len = unsafe { slice_output::format_raw(value, bytes.as_mut_ptr()) };
+    Formatted {
+        bytes,
+        len: len as u8,
+    }
+}
+
+/// Print an `f64` constant into a `&'static str` at compile time, with the
+/// same output as [`Buffer::format`][crate::Buffer::format].
+///
+/// The argument must be a constant expression of type `f64`. For `f32`, use
+/// [`Formatted::from_f32`] in a `const` item.
+///
+/// # Example
+///
+/// ```
+/// const TOLERANCE: f64 = 1e-9;
+/// const MESSAGE: &str = dtoa::const_format!(TOLERANCE);
+///
+/// assert_eq!(MESSAGE, "1e-9");
+/// ```
+#[macro_export]
+macro_rules! const_format {
+    ($value:expr) => {{
+        const FORMATTED: &$crate::Formatted = &$crate::Formatted::from_f64($value);
+        FORMATTED.as_str()
+    }};
+}
+
+impl Formatted {
+    /// Print an `f64` with the same output as [`to_string_inline`], in a
+    /// `const` context if needed.
+    ///
+    /// This runs a slower but `const` version of the same algorithm, so
+    /// prefer `to_string_inline` at runtime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dtoa::Formatted;
+    ///
+    /// const LIMITS: [Formatted; 2] = [Formatted::from_f64(0.25), Formatted::from_f64(-1e100)];
+    /// assert_eq!(LIMITS[0].as_str(), "0.25");
+    /// assert_eq!(LIMITS[1].as_str(), "-1e100");
+    /// ```
+    pub const fn from_f64(value: f64) -> Self {
+        let (bytes, len) = const_dtoa::binary64::format(value);
+        Formatted {
+            bytes,
+            len: len as u8,
+        }
+    }
+
+    /// Print an `f32` with the same output as [`to_string_inline`], in a
+    /// `const` context if needed.
+    ///
+    /// This runs a slower but `const` version of the same algorithm, so
+    /// prefer `to_string_inline` at runtime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dtoa::Formatted;
+    ///
+    /// const RATE: &Formatted = &Formatted::from_f32(0.1);
+    /// const RATE_STR: &str = RATE.as_str();
+    /// assert_eq!(RATE_STR, "0.1");
+    /// ```
+    pub const fn from_f32(value: f32) -> Self {
+        let (bytes, len) = const_dtoa::binary32::format(value);
+        Formatted {
+            bytes,
+            len: len as u8,
+        }
+    }
+
+    /// The formatted number as a string slice.
+    pub const fn as_str(&self) -> &str {
+        // The output is ASCII.
+        unsafe {
+            let bytes = slice::from_raw_parts(self.bytes.as_ptr(), self.len as usize);
+            str::from_utf8_unchecked(bytes)
+        }
+    }
+}
+
+impl Deref for Formatted {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Formatted {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for Formatted {
+    fn as_ref(&self) -> &[u8] {
+        self.as_str().as_bytes()
+    }
+}
+
+impl fmt::Display for Formatted {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), formatter)
+    }
+}
+
+impl fmt::Debug for Formatted {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), formatter)
+    }
+}
+
+impl PartialEq for Formatted {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Formatted {}
+
+impl PartialOrd for Formatted {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Formatted {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for Formatted {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
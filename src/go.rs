@@ -0,0 +1,171 @@
+use crate::{exact, format_nonfinite, layout, write_signed, Float, LargeBuffer};
+use core::str;
+
+const NAN: &str = "NaN";
+const INFINITY: &str = "+Inf";
+const NEG_INFINITY: &str = "-Inf";
+
+/// A format byte of Go's `strconv.FormatFloat`, for
+/// [`LargeBuffer::format_go`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GoFormat {
+    /// `'b'`: the integer significand and binary exponent, "-ddddp±ddd".
+    Binary,
+    /// `'e'`: "-d.dddde±dd" with at least two exponent digits.
+    LowerExp,
+    /// `'E'`: "-d.ddddE±dd" with at least two exponent digits.
+    UpperExp,
+    /// `'f'`: "-ddd.dddd" without an exponent.
+    Fixed,
+    /// `'g'`: like `'e'` if the exponent is less than -4 or at least 6, and
+    /// otherwise like `'f'`.
+    LowerGeneral,
+    /// `'G'`: like `'E'` if the exponent is less than -4 or at least 6, and
+    /// otherwise like `'f'`.
+    UpperGeneral,
+    /// `'x'`: hexadecimal mantissa and binary exponent, "-0x1.hhhhp±dd".
+    LowerHex,
+    /// `'X'`: hexadecimal mantissa and binary exponent, "-0X1.HHHHP±dd".
+    UpperHex,
+}
+
+impl LargeBuffer {
+    /// Print a floating point number the way Go's `strconv.FormatFloat` does
+    /// with precision -1, meaning the shortest digits that round trip.
+    ///
+    /// The bit size is that of the float type, so an `f32` is printed like
+    /// `FormatFloat(float64(x), fmt, -1, 32)`. See [`GoFormat`] for the
+    /// formats.
+    ///
+    /// The `'g'` and `'G'` formats switch to an exponent at 10<sup>6</sup>,
+    /// which is what `strconv` does for precision -1. Go's `fmt` package
+    /// prints `%v` with a threshold of 10<sup>21</sup> instead, so
+    /// `fmt.Sprint(1e6)` is "1e+06" as here but `fmt.Sprint(1e20)` is
+    /// "100000000000000000000", unlike this method.
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "NaN", positive infinity as "+Inf", and negative
+    /// infinity as "-Inf".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dtoa::GoFormat;
+    ///
+    /// let mut buffer = dtoa::LargeBuffer::new();
+    /// assert_eq!(buffer.format_go(1e6, GoFormat::LowerGeneral), "1e+06");
+    /// assert_eq!(buffer.format_go(1e6, GoFormat::Fixed), "1000000");
+    /// assert_eq!(buffer.format_go(0.1f32, GoFormat::LowerExp), "1e-01");
+    /// assert_eq!(buffer.format_go(1.5, GoFormat::LowerHex), "0x1.8p+00");
+    /// ```
+    pub fn format_go<F: Float>(&mut self, value: F, format: GoFormat) -> &str {
+        if value.is_nonfinite() {
+            return format_nonfinite(value, NAN, INFINITY, NEG_INFINITY);
+        }
+
+        unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                let (f, e) = value.decode();
+                match format {
+                    GoFormat::Binary => write_binary(buffer, f, e),
+                    GoFormat::LowerHex => write_hex(buffer, f, e, false),
+                    GoFormat::UpperHex => write_hex(buffer, f, e, true),
+                    _ => write_shortest(buffer, value, format),
+                }
+            })
+        }
+    }
+}
+
+// The formats that print the shortest decimal digits.
+unsafe fn write_shortest<F: Float>(buffer: *mut u8, value: F, format: GoFormat) -> *mut u8 {
+    let mut digits = [0u8; exact::SHORTEST_DIGITS];
+    let (length, k) = exact::shortest(value, &mut digits);
+    let exp = length + k - 1;
+    let general = !(-4..6).contains(&exp);
+    match format {
+        GoFormat::LowerExp => write_e(buffer, &digits, length, exp, b'e'),
+        GoFormat::UpperExp => write_e(buffer, &digits, length, exp, b'E'),
+        GoFormat::LowerGeneral if general => write_e(buffer, &digits, length, exp, b'e'),
+        GoFormat::UpperGeneral if general => write_e(buffer, &digits, length, exp, b'E'),
+        _ => layout::write_fixed(buffer, &digits, length, k, (-k).max(0)),
+    }
+}
+
+unsafe fn write_e(
+    buffer: *mut u8,
+    digits: &[u8],
+    length: isize,
+    exp: isize,
+    marker: u8,
+) -> *mut u8 {
+    let buffer = layout::write_mantissa(buffer, digits, length, length);
+    layout::write_exponent(buffer, marker, exp, true, true)
+}
+
+// 4503599627370496p-52
+unsafe fn write_binary(buffer: *mut u8, f: u64, e: isize) -> *mut u8 {
+    let buffer = write_decimal(buffer, f);
+    *buffer = b'p';
+    *buffer.add(1) = if e < 0 { b'-' } else { b'+' };
+    write_decimal(buffer.add(2), e.unsigned_abs() as u64)
+}
+
+// 0x1.8p+00, with the leading 1 of subnormals shifted into place and trailing
+// zeros of the fraction removed.
+unsafe fn write_hex(mut buffer: *mut u8, f: u64, e: isize, upper: bool) -> *mut u8 {
+    let (hex, x, p) = if upper {
+        (b"0123456789ABCDEF", b'X', b'P')
+    } else {
+        (b"0123456789abcdef", b'x', b'p')
+    };
+
+    // Move the leading 1 to bit 60, so that the fraction is a whole number of
+    // hex digits.
+    let (mut mant, exp) = if f == 0 {
+        (0, 0)
+    } else {
+        let shift = f.leading_zeros() as isize - 3;
+        (f << shift, e + 60 - shift)
+    };
+
+    *buffer = b'0';
+    *buffer.add(1) = x;
+    *buffer.add(2) = b'0' + (mant >> 60) as u8;
+    buffer = buffer.add(3);
+    mant <<= 4;
+    if mant != 0 {
+        *buffer = b'.';
+        buffer = buffer.add(1);
+        while mant != 0 {
+            *buffer = hex[(mant >> 60) as usize];
+            buffer = buffer.add(1);
+            mant <<= 4;
+        }
+    }
+
+    *buffer = p;
+    *buffer.add(1) = if exp < 0 { b'-' } else { b'+' };
+    buffer = buffer.add(2);
+    if -10 < exp && exp < 10 {
+        *buffer = b'0';
+        buffer = buffer.add(1);
+    }
+    write_decimal(buffer, exp.unsigned_abs() as u64)
+}
+
+// Writes `n` in decimal. Exponents here can have 4 digits, which
+// `layout::write_exponent` does not support.
+unsafe fn write_decimal(buffer: *mut u8, mut n: u64) -> *mut u8 {
+    let end = buffer.add(layout::count_digits(n));
+    let mut digit = end;
+    loop {
+        digit = digit.sub(1);
+        *digit = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return end;
+        }
+    }
+}
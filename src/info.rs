@@ -0,0 +1,87 @@
+use crate::{dtoa, write_signed, Buffer, Float};
+use core::str;
+#[cfg(feature = "no-panic")]
+use no_panic::no_panic;
+
+/// Facts about how [`Buffer::format`] laid out a finite number, returned by
+/// [`Buffer::format_with_info`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FormatInfo {
+    exponential: bool,
+    significant_digits: usize,
+    exponent: i32,
+    integral: bool,
+}
+
+impl FormatInfo {
+    /// Whether the number was written in exponent form, such as "1.5e30",
+    /// rather than positionally.
+    pub fn is_exponential(&self) -> bool {
+        self.exponential
+    }
+
+    /// The number of significant decimal digits produced, which is 1 for
+    /// zero.
+    pub fn significant_digits(&self) -> usize {
+        self.significant_digits
+    }
+
+    /// The decimal exponent of the leading digit, so that the magnitude lies
+    /// in `[10^exponent, 10^(exponent+1))`. This is 0 for zero.
+    pub fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    /// Whether the printed value is an integer.
+    pub fn is_integral(&self) -> bool {
+        self.integral
+    }
+}
+
+impl Buffer {
+    /// Print a floating point number into this buffer like [`format`], and
+    /// also return facts about the layout that would otherwise require parsing
+    /// the output.
+    ///
+    /// [`format`]: Buffer::format
+    ///
+    /// # Special cases
+    ///
+    /// NaN and infinities are formatted the same as by `format`, and no
+    /// [`FormatInfo`] is returned for them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::Buffer::new();
+    /// let (printed, info) = buffer.format_with_info(1.5e30);
+    /// assert_eq!(printed, "1.5e30");
+    /// let info = info.unwrap();
+    /// assert!(info.is_exponential());
+    /// assert_eq!(info.significant_digits(), 2);
+    /// assert_eq!(info.exponent(), 30);
+    /// assert!(info.is_integral());
+    /// ```
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    pub fn format_with_info<F: Float>(&mut self, value: F) -> (&str, Option<FormatInfo>) {
+        if value.is_nonfinite() {
+            return (value.format_nonfinite(), None);
+        }
+
+        let mut info = None;
+        let printed = unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                let (length, k) = value.grisu(buffer);
+                let kk = length + k;
+                info = Some(FormatInfo {
+                    exponential: dtoa::STANDARD.is_exponential(kk),
+                    significant_digits: length as usize,
+                    exponent: (kk - 1) as i32,
+                    integral: k >= 0,
+                });
+                dtoa::prettify(buffer, length, k, dtoa::STANDARD)
+            })
+        };
+        (printed, info)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::dtoa::{self, Style};
+use crate::{exact, format_nonfinite, write_signed, Buffer, Float};
+use core::{ptr, str};
+
+const NAN: &str = "NaN";
+const INFINITY: &str = "Infinity";
+const NEG_INFINITY: &str = "-Infinity";
+
+// Positional notation for 10^-3 <= v < 10^7, and "1.0E30" rather than "1E30".
+const JAVA: Style = Style {
+    min_kk: -3,
+    max_kk: 7,
+    point_zero: true,
+    mantissa_point_zero: true,
+    exponent_marker: b'E',
+};
+
+impl Buffer {
+    /// Print a floating point number the way Java's `Double.toString` and
+    /// `Float.toString` do as of JDK 19.
+    ///
+    /// The output uses the shortest digits that round trip, choosing the
+    /// closest if there are several, except that when a single digit would do,
+    /// the closest of up to two digits is used instead. Computerized scientific
+    /// notation is used if the magnitude is less than 10<sup>-3</sup> or at
+    /// least 10<sup>7</sup>. At least one digit is always printed after the
+    /// decimal point.
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "NaN", positive infinity as "Infinity", and negative
+    /// infinity as "-Infinity".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::Buffer::new();
+    /// assert_eq!(buffer.format_java(1e7), "1.0E7");
+    /// assert_eq!(buffer.format_java(0.001), "0.001");
+    /// assert_eq!(buffer.format_java(5e-324), "4.9E-324");
+    /// ```
+    pub fn format_java<F: Float>(&mut self, value: F) -> &str {
+        if value.is_nonfinite() {
+            return format_nonfinite(value, NAN, INFINITY, NEG_INFINITY);
+        }
+
+        let mut digits = [0u8; exact::SHORTEST_DIGITS];
+        let (length, k) = exact::shortest_at_least(value, 2, &mut digits);
+        unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                ptr::copy_nonoverlapping(digits.as_ptr(), buffer, length as usize);
+                dtoa::prettify(buffer, length, k, JAVA)
+            })
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// Layout helpers shared by the formatting modes that work from a separate array
+// of digits, as opposed to `prettify` which rearranges digits in place.
+
+use crate::dtoa;
+
+// Writes the integer part followed by exactly `fraction` digits after the
+// decimal point, padding with zeros. The digits must not extend past the last
+// fractional position. A length of 0 is zero.
+pub unsafe fn write_fixed(
+    mut buffer: *mut u8,
+    digits: &[u8],
+    length: isize,
+    k: isize,
+    fraction: isize,
+) -> *mut u8 {
+    let kk = if length == 0 { 0 } else { length + k };
+    let digit = |i: isize| {
+        if 0 <= i && i < length {
+            digits[i as usize]
+        } else {
+            b'0'
+        }
+    };
+
+    if kk <= 0 {
+        *buffer = b'0';
+        buffer = buffer.add(1);
+    }
+    for i in 0..kk {
+        *buffer = digit(i);
+        buffer = buffer.add(1);
+    }
+    if fraction > 0 {
+        *buffer = b'.';
+        buffer = buffer.add(1);
+        for i in kk..kk + fraction {
+            *buffer = digit(i);
+            buffer = buffer.add(1);
+        }
+    }
+    buffer
+}
+
+// Writes exactly `precision` significant digits with the decimal point after
+// the first, like "1.2300", padding with zeros. The digits must fit within the
+// precision.
+pub unsafe fn write_mantissa(
+    mut buffer: *mut u8,
+    digits: &[u8],
+    length: isize,
+    precision: isize,
+) -> *mut u8 {
+    *buffer = digits[0];
+    buffer = buffer.add(1);
+    if precision > 1 {
+        *buffer = b'.';
+        buffer = buffer.add(1);
+        for i in 1..precision {
+            *buffer = if i < length { digits[i as usize] } else { b'0' };
+            buffer = buffer.add(1);
+        }
+    }
+    buffer
+}
+
+// Writes `marker` followed by the exponent, with a plus sign for nonnegative
+// exponents if `plus` is set, and at least two digits if `pad` is set. For
+// example "e+5", "e+05", "E-5".
+pub unsafe fn write_exponent(
+    mut buffer: *mut u8,
+    marker: u8,
+    exp: isize,
+    plus: bool,
+    pad: bool,
+) -> *mut u8 {
+    *buffer = marker;
+    buffer = buffer.add(1);
+    if exp < 0 {
+        *buffer = b'-';
+        buffer = buffer.add(1);
+    } else if plus {
+        *buffer = b'+';
+        buffer = buffer.add(1);
+    }
+    if pad && -10 < exp && exp < 10 {
+        *buffer = b'0';
+        buffer = buffer.add(1);
+    }
+    dtoa::write_exponent(exp.abs(), buffer)
+}
+
+// Number of bytes written by `write_exponent` without `plus` or `pad`, like 3
+// for "e-5".
+pub fn exponent_len(exp: isize) -> isize {
+    1 + isize::from(exp < 0) + count_digits(exp.unsigned_abs() as u64) as isize
+}
+
+// Number of decimal digits of `n`, which is 1 for 0.
+pub fn count_digits(mut n: u64) -> usize {
+    let mut count = 1;
+    while n >= 10 {
+        n /= 10;
+        count += 1;
+    }
+    count
+}
@@ -0,0 +1,42 @@
+use crate::{dtoa, layout, Float};
+use core::mem::MaybeUninit;
+#[cfg(feature = "no-panic")]
+use no_panic::no_panic;
+
+/// Return the length in bytes of the string that [`Buffer::format`] would
+/// print for a floating point number, without laying it out.
+///
+/// This is useful for sizing buffers or aligning columns before writing. The
+/// result is always exact. Integers small enough that every neighbouring
+/// float is at most 1 away, which covers typical counters and ids, are
+/// measured from their integer value without generating digits; other values
+/// still run the digit generator but skip the layout step.
+///
+/// [`Buffer::format`]: crate::Buffer::format
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(dtoa::formatted_len(1.5), 3);
+/// assert_eq!(dtoa::formatted_len(-1200.0), 7);
+/// assert_eq!(dtoa::formatted_len(1e30), 4);
+/// assert_eq!(dtoa::formatted_len(f64::NEG_INFINITY), 4);
+/// ```
+#[cfg_attr(feature = "no-panic", no_panic)]
+pub fn formatted_len<F: Float>(value: F) -> usize {
+    if value.is_nonfinite() {
+        return value.format_nonfinite().len();
+    }
+
+    let sign = value.is_sign_negative() as usize;
+    let (f, e) = value.decode();
+    if e <= 0 && e > -64 && f & ((1 << -e) - 1) == 0 {
+        // An integer below 2^64 whose spacing to the neighbouring floats is at
+        // most 1, so the shortest digits are its own: "1234.0".
+        return sign + layout::count_digits(f >> -e) + 2;
+    }
+
+    let mut digits = [MaybeUninit::<u8>::uninit(); 24];
+    let (length, k) = unsafe { value.grisu(digits.as_mut_ptr().cast::<u8>()) };
+    sign + dtoa::prettified_len(length, k, dtoa::STANDARD) as usize
+}
@@ -61,10 +61,51 @@
     clippy::unseparated_literal_suffix
 )]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+mod append;
+#[cfg(feature = "bytes")]
+mod bufmut;
+mod const_dtoa;
 #[macro_use]
 mod diyfp;
 #[macro_use]
 mod dtoa;
+mod ecma;
+mod exact;
+mod exact_buffer;
+mod fit;
+mod formatted;
+mod go;
+mod info;
+mod java;
+mod layout;
+mod length;
+mod openmetrics;
+mod options;
+mod postgres;
+pub mod printf;
+mod python;
+mod slice_output;
+pub mod sql;
+pub mod svg;
+mod utf16;
+mod xsd;
+
+#[cfg(feature = "alloc")]
+pub use crate::append::{append, append_string};
+#[cfg(feature = "bytes")]
+pub use crate::bufmut::BufMutExt;
+pub use crate::exact_buffer::ExactBuffer;
+pub use crate::formatted::{to_string_inline, Formatted};
+pub use crate::go::GoFormat;
+pub use crate::info::FormatInfo;
+pub use crate::length::formatted_len;
+pub use crate::options::{Digits, ExponentStyle, Layout, Options};
+pub use crate::slice_output::{format_to_slice, BufferTooSmall};
+pub use crate::utf16::Buffer16;
 
 use core::mem::MaybeUninit;
 use core::slice;
@@ -154,28 +195,168 @@ impl Buffer {
     /// [`is_infinite`]: f64::is_infinite
     #[cfg_attr(feature = "no-panic", no_panic)]
     pub fn format_finite<F: Float>(&mut self, value: F) -> &str {
-        value.write(self)
+        if value.is_zero() {
+            return if value.is_sign_negative() {
+                "-0.0"
+            } else {
+                "0.0"
+            };
+        }
+        unsafe {
+            let start = self.bytes.as_mut_ptr().cast::<u8>();
+            let end = dtoa::dtoa(value, start);
+            let len = end as usize - start as usize;
+            str::from_utf8_unchecked(slice::from_raw_parts(start, len))
+        }
+    }
+}
+
+/// A stack allocation for the formatting methods whose output can be longer
+/// than fits in a [`Buffer`].
+///
+/// These print a caller-chosen number of digits, such as
+/// [`to_fixed`][LargeBuffer::to_fixed], or lay out every float positionally,
+/// such as [`format_xsd_decimal`][LargeBuffer::format_xsd_decimal], which
+/// takes 327 bytes for the smallest negative subnormal `f64`.
+///
+/// # Example
+///
+/// ```
+/// let mut buffer = dtoa::LargeBuffer::new();
+/// let printed = buffer.to_fixed(0.1, 30);
+/// assert_eq!(printed, "0.100000000000000005551115123126");
+/// ```
+pub struct LargeBuffer {
+    bytes: [MaybeUninit<u8>; LARGE_BUFFER_LEN],
+}
+
+// The longest output is the positional form of the negative smallest subnormal
+// f64 printed by `format_go` with format 'f' or by `format_xsd_decimal`: "-0."
+// followed by 323 zeros and a 5.
+const LARGE_BUFFER_LEN: usize = 327;
+
+impl Default for LargeBuffer {
+    #[inline]
+    fn default() -> LargeBuffer {
+        LargeBuffer::new()
+    }
+}
+
+impl Copy for LargeBuffer {}
+
+#[allow(clippy::non_canonical_clone_impl)]
+impl Clone for LargeBuffer {
+    #[inline]
+    fn clone(&self) -> Self {
+        LargeBuffer::new()
+    }
+}
+
+impl LargeBuffer {
+    /// This is a cheap operation; you don't need to worry about reusing buffers
+    /// for efficiency.
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    pub fn new() -> LargeBuffer {
+        let bytes = [MaybeUninit::<u8>::uninit(); LARGE_BUFFER_LEN];
+        LargeBuffer { bytes }
+    }
+}
+
+// Writes a minus sign if `negative`, then the rest of the output through
+// `write`, which receives the position after the sign and returns the end.
+#[inline]
+unsafe fn write_signed(
+    bytes: &mut [MaybeUninit<u8>],
+    negative: bool,
+    write: impl FnOnce(*mut u8) -> *mut u8,
+) -> &str {
+    let start = bytes.as_mut_ptr().cast::<u8>();
+    let mut buffer = start;
+    if negative {
+        *buffer = b'-';
+        buffer = buffer.add(1);
+    }
+    let end = write(buffer);
+    let len = end as usize - start as usize;
+    str::from_utf8_unchecked(slice::from_raw_parts(start, len))
+}
+
+// For output formats that spell NaN and the infinities differently from
+// std::fmt.
+#[cold]
+#[cfg_attr(feature = "no-panic", no_panic)]
+fn format_nonfinite<F: Float>(
+    value: F,
+    nan: &'static str,
+    infinity: &'static str,
+    neg_infinity: &'static str,
+) -> &'static str {
+    if value.is_nan() {
+        nan
+    } else if value.is_sign_negative() {
+        neg_infinity
+    } else {
+        infinity
     }
 }
 
 /// A floating point number that can be written into a [`dtoa::Buffer`][Buffer].
 ///
 /// This trait is sealed and cannot be implemented for types outside of dtoa.
-pub trait Float: private::Sealed {}
+pub trait Float: private::Sealed {
+    /// The length of the longest string printed by [`Buffer::format`] and
+    /// [`format_to_slice`] for this type, such as "-0.0000012345678901234567"
+    /// for `f64` or "-100000000000000000000.0" for `f32`.
+    const MAX_LEN: usize;
+}
 
-impl Float for f32 {}
-impl Float for f64 {}
+impl Float for f32 {
+    const MAX_LEN: usize = 24;
+}
+
+impl Float for f64 {
+    const MAX_LEN: usize = 25;
+}
 
 // Seal to prevent downstream implementations of Float trait.
 mod private {
     pub trait Sealed: Copy {
+        // Number of significant decimal digits that always survive a round
+        // trip through the type, like `f64::DIGITS`.
+        const DIGITS: isize;
+
         fn is_nonfinite(self) -> bool;
         fn format_nonfinite(self) -> &'static str;
-        fn write(self, buf: &mut crate::Buffer) -> &str;
+        fn is_nan(self) -> bool;
+        fn is_zero(self) -> bool;
+        fn is_sign_negative(self) -> bool;
+
+        // Returns the significand and binary exponent of the absolute value,
+        // which must be finite.
+        fn decode(self) -> (u64, isize);
+
+        // Whether the gap to the next smaller float is half the gap to the next
+        // larger one, which happens at powers of 2 above the subnormal range.
+        fn is_lower_boundary_closer(self) -> bool;
+
+        // Writes the shortest digits of the absolute value, which must be
+        // finite, and returns length and k. Zero is written as the single
+        // digit "0".
+        unsafe fn grisu(self, buffer: *mut u8) -> (isize, isize);
+
+        // Writes the shortest digits of the absolute value, which must be
+        // finite and nonzero, choosing the closest if there are several, and
+        // returns length and k. If they are shorter than `min_length`, the
+        // closest digits of `min_length` must be the same followed by zeros.
+        // Returns None if that cannot be established quickly.
+        unsafe fn grisu3(self, min_length: isize, buffer: *mut u8) -> Option<(isize, isize)>;
     }
 }
 
 impl private::Sealed for f32 {
+    const DIGITS: isize = f32::DIGITS as isize;
+
     #[inline]
     #[cfg_attr(feature = "no-panic", no_panic)]
     fn is_nonfinite(self) -> bool {
@@ -200,28 +381,74 @@ impl private::Sealed for f32 {
     }
 
     #[inline]
-    fn write(self, buf: &mut Buffer) -> &str {
-        dtoa! {
-            floating_type: f32,
-            significand_type: u32,
-            exponent_type: i32,
-
-            diy_significand_size: 32,
-            significand_size: 23,
-            exponent_bias: 0x7F,
-            mask_type: u32,
-            exponent_mask: 0x7F800000,
-            significand_mask: 0x007FFFFF,
-            hidden_bit: 0x00800000,
-            cached_powers_f: CACHED_POWERS_F_32,
-            cached_powers_e: CACHED_POWERS_E_32,
-            min_power: (-36),
-        };
-        unsafe { dtoa(buf, self) }
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn is_nan(self) -> bool {
+        const EXP_MASK: u32 = 0x7f800000;
+        const MANTISSA_MASK: u32 = 0x007fffff;
+        let bits = self.to_bits();
+        bits & EXP_MASK == EXP_MASK && bits & MANTISSA_MASK != 0
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn is_zero(self) -> bool {
+        const ABS_MASK: u32 = 0x7fffffff;
+        let bits = self.to_bits();
+        bits & ABS_MASK == 0
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn is_sign_negative(self) -> bool {
+        const SIGN_MASK: u32 = 0x80000000;
+        let bits = self.to_bits();
+        bits & SIGN_MASK != 0
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn decode(self) -> (u64, isize) {
+        const EXP_MASK: u32 = 0x7f800000;
+        const MANTISSA_MASK: u32 = 0x007fffff;
+        const HIDDEN_BIT: u32 = 0x00800000;
+        let bits = self.to_bits();
+        let biased_e = ((bits & EXP_MASK) >> 23) as isize;
+        let significand = bits & MANTISSA_MASK;
+        if biased_e != 0 {
+            ((significand | HIDDEN_BIT) as u64, biased_e - 150)
+        } else {
+            (significand as u64, 1 - 150)
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn is_lower_boundary_closer(self) -> bool {
+        const EXP_MASK: u32 = 0x7f800000;
+        const MANTISSA_MASK: u32 = 0x007fffff;
+        let bits = self.to_bits();
+        bits & MANTISSA_MASK == 0 && (bits & EXP_MASK) >> 23 > 1
+    }
+
+    #[inline]
+    unsafe fn grisu(self, buffer: *mut u8) -> (isize, isize) {
+        if self == 0.0 {
+            *buffer = b'0';
+            (1, 0)
+        } else {
+            grisu_f32::grisu2(self, buffer)
+        }
+    }
+
+    #[inline]
+    unsafe fn grisu3(self, min_length: isize, buffer: *mut u8) -> Option<(isize, isize)> {
+        grisu_f32::grisu3(self, min_length, buffer)
     }
 }
 
 impl private::Sealed for f64 {
+    const DIGITS: isize = f64::DIGITS as isize;
+
     #[inline]
     #[cfg_attr(feature = "no-panic", no_panic)]
     fn is_nonfinite(self) -> bool {
@@ -246,24 +473,114 @@ impl private::Sealed for f64 {
     }
 
     #[inline]
-    fn write(self, buf: &mut Buffer) -> &str {
-        dtoa! {
-            floating_type: f64,
-            significand_type: u64,
-            exponent_type: isize,
-
-            diy_significand_size: 64,
-            significand_size: 52,
-            exponent_bias: 0x3FF,
-            mask_type: u64,
-            exponent_mask: 0x7FF0000000000000,
-            significand_mask: 0x000FFFFFFFFFFFFF,
-            hidden_bit: 0x0010000000000000,
-            cached_powers_f: CACHED_POWERS_F_64,
-            cached_powers_e: CACHED_POWERS_E_64,
-            min_power: (-348),
-        };
-        unsafe { dtoa(buf, self) }
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn is_nan(self) -> bool {
+        const EXP_MASK: u64 = 0x7ff0000000000000;
+        const MANTISSA_MASK: u64 = 0x000fffffffffffff;
+        let bits = self.to_bits();
+        bits & EXP_MASK == EXP_MASK && bits & MANTISSA_MASK != 0
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn is_zero(self) -> bool {
+        const ABS_MASK: u64 = 0x7fffffffffffffff;
+        let bits = self.to_bits();
+        bits & ABS_MASK == 0
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn is_sign_negative(self) -> bool {
+        const SIGN_MASK: u64 = 0x8000000000000000;
+        let bits = self.to_bits();
+        bits & SIGN_MASK != 0
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn decode(self) -> (u64, isize) {
+        const EXP_MASK: u64 = 0x7ff0000000000000;
+        const MANTISSA_MASK: u64 = 0x000fffffffffffff;
+        const HIDDEN_BIT: u64 = 0x0010000000000000;
+        let bits = self.to_bits();
+        let biased_e = ((bits & EXP_MASK) >> 52) as isize;
+        let significand = bits & MANTISSA_MASK;
+        if biased_e != 0 {
+            (significand | HIDDEN_BIT, biased_e - 1075)
+        } else {
+            (significand, 1 - 1075)
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    fn is_lower_boundary_closer(self) -> bool {
+        const EXP_MASK: u64 = 0x7ff0000000000000;
+        const MANTISSA_MASK: u64 = 0x000fffffffffffff;
+        let bits = self.to_bits();
+        bits & MANTISSA_MASK == 0 && (bits & EXP_MASK) >> 52 > 1
+    }
+
+    #[inline]
+    unsafe fn grisu(self, buffer: *mut u8) -> (isize, isize) {
+        if self == 0.0 {
+            *buffer = b'0';
+            (1, 0)
+        } else {
+            grisu_f64::grisu2(self, buffer)
+        }
+    }
+
+    #[inline]
+    unsafe fn grisu3(self, min_length: isize, buffer: *mut u8) -> Option<(isize, isize)> {
+        grisu_f64::grisu3(self, min_length, buffer)
+    }
+}
+
+mod grisu_f32 {
+    use crate::{diyfp, dtoa, CACHED_POWERS_E_32, CACHED_POWERS_F_32};
+    #[cfg(feature = "no-panic")]
+    use no_panic::no_panic;
+
+    dtoa! {
+        floating_type: f32,
+        significand_type: u32,
+        exponent_type: i32,
+
+        diy_significand_size: 32,
+        significand_size: 23,
+        exponent_bias: 0x7F,
+        mask_type: u32,
+        exponent_mask: 0x7F800000,
+        significand_mask: 0x007FFFFF,
+        hidden_bit: 0x00800000,
+        cached_powers_f: CACHED_POWERS_F_32,
+        cached_powers_e: CACHED_POWERS_E_32,
+        min_power: (-36),
+    }
+}
+
+mod grisu_f64 {
+    use crate::{diyfp, dtoa, CACHED_POWERS_E_64, CACHED_POWERS_F_64};
+    #[cfg(feature = "no-panic")]
+    use no_panic::no_panic;
+
+    dtoa! {
+        floating_type: f64,
+        significand_type: u64,
+        exponent_type: isize,
+
+        diy_significand_size: 64,
+        significand_size: 52,
+        exponent_bias: 0x3FF,
+        mask_type: u64,
+        exponent_mask: 0x7FF0000000000000,
+        significand_mask: 0x000FFFFFFFFFFFFF,
+        hidden_bit: 0x0010000000000000,
+        cached_powers_f: CACHED_POWERS_F_64,
+        cached_powers_e: CACHED_POWERS_E_64,
+        min_power: (-348),
     }
 }
 
@@ -280,20 +597,20 @@ static DEC_DIGITS_LUT: [u8; 200] = *b"\
 
 // 10^-36, 10^-28, ..., 10^52
 #[rustfmt::skip]
-static CACHED_POWERS_F_32: [u32; 12] = [
+const CACHED_POWERS_F_32: [u32; 12] = [
     0xaa242499, 0xfd87b5f3, 0xbce50865, 0x8cbccc09,
     0xd1b71759, 0x9c400000, 0xe8d4a510, 0xad78ebc6,
     0x813f3979, 0xc097ce7c, 0x8f7e32ce, 0xd5d238a5,
 ];
 
 #[rustfmt::skip]
-static CACHED_POWERS_E_32: [i16; 12] = [
+const CACHED_POWERS_E_32: [i16; 12] = [
     -151, -125, -98, -71, -45, -18, 8, 35, 62, 88, 115, 141,
 ];
 
 // 10^-348, 10^-340, ..., 10^340
 #[rustfmt::skip]
-static CACHED_POWERS_F_64: [u64; 87] = [
+const CACHED_POWERS_F_64: [u64; 87] = [
     0xfa8fd5a0081c0288, 0xbaaee17fa23ebf76,
     0x8b16fb203055ac76, 0xcf42894a5dce35ea,
     0x9a6bb0aa55653b2d, 0xe61acf033d1a45df,
@@ -341,7 +658,7 @@ static CACHED_POWERS_F_64: [u64; 87] = [
 ];
 
 #[rustfmt::skip]
-static CACHED_POWERS_E_64: [i16; 87] = [
+const CACHED_POWERS_E_64: [i16; 87] = [
     -1220, -1193, -1166, -1140, -1113, -1087, -1060, -1034, -1007,  -980,
     -954,   -927,  -901,  -874,  -847,  -821,  -794,  -768,  -741,  -715,
     -688,   -661,  -635,  -608,  -582,  -555,  -529,  -502,  -475,  -449,
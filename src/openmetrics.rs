@@ -0,0 +1,43 @@
+use crate::{format_nonfinite, Buffer, Float};
+#[cfg(feature = "no-panic")]
+use no_panic::no_panic;
+
+const NAN: &str = "NaN";
+const INFINITY: &str = "+Inf";
+const NEG_INFINITY: &str = "-Inf";
+
+impl Buffer {
+    /// Print a floating point number for the Prometheus text exposition format
+    /// or OpenMetrics, as a sample value or as the bound in a histogram's `le`
+    /// label.
+    ///
+    /// Finite values are formatted the same as by [`format_finite`], which
+    /// always includes a decimal point or exponent, so a bucket bound of 1 is
+    /// "1.0" rather than "1". The output consists only of ASCII digits and the
+    /// characters `+-.eInfNa`, so it can be placed between the quotes of a
+    /// label value without escaping.
+    ///
+    /// [`format_finite`]: Buffer::format_finite
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "NaN", positive infinity as "+Inf", and negative
+    /// infinity as "-Inf".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::Buffer::new();
+    /// assert_eq!(buffer.format_openmetrics(1.0), "1.0");
+    /// assert_eq!(buffer.format_openmetrics(0.25), "0.25");
+    /// assert_eq!(buffer.format_openmetrics(f64::INFINITY), "+Inf");
+    /// ```
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    pub fn format_openmetrics<F: Float>(&mut self, value: F) -> &str {
+        if value.is_nonfinite() {
+            format_nonfinite(value, NAN, INFINITY, NEG_INFINITY)
+        } else {
+            self.format_finite(value)
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use crate::dtoa::{self, Style};
+use crate::{
+    format_nonfinite, write_signed, Buffer, Float, LargeBuffer, INFINITY, NAN, NEG_INFINITY,
+};
+use core::mem::MaybeUninit;
+use core::{ptr, slice, str};
+
+/// Options for [`Buffer::format_with`] and [`LargeBuffer::format_with`].
+///
+/// The default options produce the same output as [`Buffer::format`].
+///
+/// ```
+/// use dtoa::{Layout, Options};
+///
+/// let options = Options::new().layout(Layout::Shortest);
+/// let mut buffer = dtoa::Buffer::new();
+/// assert_eq!(buffer.format_with(100000.0, &options), Some("1e5"));
+/// assert_eq!(buffer.format_with(0.0001, &options), Some("1e-4"));
+/// assert_eq!(buffer.format_with(1234.0, &options), Some("1234"));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    layout: Layout,
+    exponent_style: ExponentStyle,
+    digits: Digits,
+    point_zero: bool,
+    nan: &'static str,
+    infinity: &'static str,
+    neg_infinity: &'static str,
+}
+
+/// How the digits of a number are laid out around the decimal point.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// Positional notation if the magnitude is at least 10<sup>-6</sup> and
+    /// less than 10<sup>21</sup>, otherwise exponential notation, with ".0"
+    /// appended to integers unless disabled by [`Options::point_zero`]:
+    /// "100000.0", "0.0001", "1e21". This is the layout of [`Buffer::format`].
+    ///
+    /// [`Buffer::format`]: crate::Buffer::format
+    Standard,
+    /// Whichever of positional and exponential notation is shorter, preferring
+    /// positional if they are equally long, and without ".0" on integers:
+    /// "1e5", "1e-3", "1234", "0.01".
+    Shortest,
+    /// Always exponential notation: "1e5", "1.5e0", "1.23e-5".
+    Scientific,
+}
+
+/// How the exponent is written in exponential notation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExponentStyle {
+    /// An `e` followed by the exponent: "1.23e-5".
+    Plain,
+    /// LaTeX math: "1.23 \times 10^{-5}".
+    Latex,
+    /// A multiplication sign and the exponent in Unicode superscript digits:
+    /// "1.23×10⁻⁵".
+    Unicode,
+}
+
+/// The script in which decimal digits are written.
+///
+/// Only the digits of the mantissa and exponent change; the decimal point,
+/// sign, exponent marker and the base 10 of LaTeX and Unicode exponents stay
+/// ASCII, and Unicode superscript exponents are unaffected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Digits {
+    /// ASCII digits 0123456789.
+    Ascii,
+    /// Arabic-Indic digits ٠١٢٣٤٥٦٧٨٩, U+0660 to U+0669.
+    ArabicIndic,
+    /// Extended Arabic-Indic digits ۰۱۲۳۴۵۶۷۸۹ used for Persian and Urdu,
+    /// U+06F0 to U+06F9.
+    ExtendedArabicIndic,
+    /// Devanagari digits ०१२३४५६७८९, U+0966 to U+096F.
+    Devanagari,
+    /// Fullwidth digits ０１２３４５６７８９, U+FF10 to U+FF19.
+    Fullwidth,
+}
+
+impl Digits {
+    // The UTF-8 encoding of each digit, all of the same length.
+    fn encodings(self) -> [&'static str; 10] {
+        match self {
+            Digits::Ascii => ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"],
+            Digits::ArabicIndic => ["٠", "١", "٢", "٣", "٤", "٥", "٦", "٧", "٨", "٩"],
+            Digits::ExtendedArabicIndic => ["۰", "۱", "۲", "۳", "۴", "۵", "۶", "۷", "۸", "۹"],
+            Digits::Devanagari => ["०", "१", "२", "३", "४", "५", "६", "७", "८", "९"],
+            Digits::Fullwidth => ["０", "１", "２", "３", "４", "５", "６", "７", "８", "９"],
+        }
+    }
+}
+
+impl Options {
+    /// The default options, which produce the same output as
+    /// [`Buffer::format`][crate::Buffer::format].
+    pub const fn new() -> Self {
+        Options {
+            layout: Layout::Standard,
+            exponent_style: ExponentStyle::Plain,
+            digits: Digits::Ascii,
+            point_zero: true,
+            nan: NAN,
+            infinity: INFINITY,
+            neg_infinity: NEG_INFINITY,
+        }
+    }
+
+    /// Options that produce valid TOML float literals: "1.0", "1e21", "inf",
+    /// "-inf", "nan".
+    ///
+    /// Negative zero is printed as "-0.0", which TOML reads as negative zero.
+    /// Changing the layout or omitting ".0" would print integral values as
+    /// TOML integers.
+    pub const fn toml() -> Self {
+        Options::new().nonfinite("nan", "inf", "-inf")
+    }
+
+    /// Options that produce float literals of the YAML 1.2 core schema: "1.0",
+    /// "1e21", ".inf", "-.inf", ".nan".
+    ///
+    /// Negative zero is printed as "-0.0", which YAML reads as negative zero.
+    /// Changing the layout or omitting ".0" would print integral values as
+    /// YAML integers.
+    pub const fn yaml() -> Self {
+        Options::new().nonfinite(".nan", ".inf", "-.inf")
+    }
+
+    /// Set how digits are laid out around the decimal point.
+    #[must_use]
+    pub const fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Set how the exponent is written in exponential notation.
+    ///
+    /// ```
+    /// use dtoa::{ExponentStyle, Layout, Options};
+    ///
+    /// let options = Options::new().layout(Layout::Scientific);
+    /// let mut buffer = dtoa::LargeBuffer::new();
+    ///
+    /// let latex = options.exponent_style(ExponentStyle::Latex);
+    /// assert_eq!(buffer.format_with(1.23e-5, &latex), "1.23 \\times 10^{-5}");
+    ///
+    /// let unicode = options.exponent_style(ExponentStyle::Unicode);
+    /// assert_eq!(buffer.format_with(1.23e-5, &unicode), "1.23×10⁻⁵");
+    /// ```
+    #[must_use]
+    pub const fn exponent_style(mut self, exponent_style: ExponentStyle) -> Self {
+        self.exponent_style = exponent_style;
+        self
+    }
+
+    /// Set the script in which decimal digits are written.
+    ///
+    /// ```
+    /// use dtoa::{Digits, Options};
+    ///
+    /// let options = Options::new().digits(Digits::Devanagari);
+    /// let mut buffer = dtoa::LargeBuffer::new();
+    /// assert_eq!(buffer.format_with(-12.5, &options), "-१२.५");
+    /// ```
+    #[must_use]
+    pub const fn digits(mut self, digits: Digits) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Set whether integral values in the standard layout end in ".0", as in
+    /// "123.0" and "0.0", which is the default. If not, they are printed as
+    /// "123" and "0" and all other output is unchanged.
+    ///
+    /// The shortest layout never prints ".0".
+    #[must_use]
+    pub const fn point_zero(mut self, point_zero: bool) -> Self {
+        self.point_zero = point_zero;
+        self
+    }
+
+    /// Set the strings printed for NaN of either sign, positive infinity, and
+    /// negative infinity. The defaults are "NaN", "inf" and "-inf" to match
+    /// std::fmt.
+    #[must_use]
+    pub const fn nonfinite(
+        mut self,
+        nan: &'static str,
+        infinity: &'static str,
+        neg_infinity: &'static str,
+    ) -> Self {
+        self.nan = nan;
+        self.infinity = infinity;
+        self.neg_infinity = neg_infinity;
+        self
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options::new()
+    }
+}
+
+impl Buffer {
+    /// Print a floating point number into this buffer according to `options`
+    /// and return a reference to its string representation within the buffer.
+    ///
+    /// The output is the same as from [`LargeBuffer::format_with`], which
+    /// accepts every option.
+    ///
+    /// Returns `None` if `options` use the LaTeX or Unicode exponent style or
+    /// digits other than ASCII, whose output can be longer than fits in a
+    /// `Buffer`. Every layout on its own fits, as does any value of
+    /// [`Options::point_zero`] and [`Options::nonfinite`].
+    ///
+    /// ```
+    /// use dtoa::{Digits, Options};
+    ///
+    /// let mut buffer = dtoa::Buffer::new();
+    /// let options = Options::new().point_zero(false);
+    /// assert_eq!(buffer.format_with(123.0, &options), Some("123"));
+    ///
+    /// let options = Options::new().digits(Digits::Devanagari);
+    /// assert_eq!(buffer.format_with(123.0, &options), None);
+    /// ```
+    pub fn format_with<F: Float>(&mut self, value: F, options: &Options) -> Option<&str> {
+        // Without these, no layout is longer than the standard one, which is
+        // the output of `Buffer::format` or shorter.
+        if options.exponent_style != ExponentStyle::Plain || options.digits != Digits::Ascii {
+            return None;
+        }
+        Some(unsafe { format_with(&mut self.bytes, value, options) })
+    }
+}
+
+impl LargeBuffer {
+    /// Print a floating point number into this buffer according to `options`
+    /// and return a reference to its string representation within the buffer.
+    ///
+    /// The digits are the same shortest digits that round trip as printed by
+    /// [`Buffer::format`], so every layout reads back as the same value.
+    ///
+    /// # Special cases
+    ///
+    /// NaN and infinities are formatted as the strings set by
+    /// [`Options::nonfinite`], by default "NaN", "inf" and "-inf" to match
+    /// std::fmt.
+    pub fn format_with<F: Float>(&mut self, value: F, options: &Options) -> &str {
+        unsafe { format_with(&mut self.bytes, value, options) }
+    }
+}
+
+// The caller guarantees that `bytes` is long enough for the options.
+unsafe fn format_with<'a, F: Float>(
+    bytes: &'a mut [MaybeUninit<u8>],
+    value: F,
+    options: &Options,
+) -> &'a str {
+    if value.is_nonfinite() {
+        return format_nonfinite(value, options.nan, options.infinity, options.neg_infinity);
+    }
+
+    write_signed(bytes, value.is_sign_negative(), |buffer| {
+        let (length, k) = value.grisu(buffer);
+        let end = match options.layout {
+            Layout::Standard => {
+                let style = Style {
+                    point_zero: options.point_zero,
+                    ..dtoa::STANDARD
+                };
+                dtoa::prettify(buffer, length, k, style)
+            }
+            Layout::Shortest => prettify_shortest(buffer, length, k),
+            Layout::Scientific => dtoa::prettify(buffer, length, k, SCIENTIFIC),
+        };
+        if options.exponent_style == ExponentStyle::Plain && options.digits == Digits::Ascii {
+            end
+        } else {
+            restyle(buffer, end, options.exponent_style, options.digits)
+        }
+    })
+}
+
+// Positional notation for any magnitude, without ".0" on integers.
+const POSITIONAL: Style = Style {
+    min_kk: isize::MIN,
+    max_kk: isize::MAX,
+    point_zero: false,
+    ..dtoa::STANDARD
+};
+
+// Exponential notation for any magnitude.
+const SCIENTIFIC: Style = Style {
+    min_kk: 0,
+    max_kk: 0,
+    ..dtoa::STANDARD
+};
+
+// Whichever of positional and exponential notation is shorter.
+unsafe fn prettify_shortest(buffer: *mut u8, length: isize, k: isize) -> *mut u8 {
+    let positional_len = dtoa::positional_len(length, k);
+    let exponential_len = dtoa::exponential_len(length, k, SCIENTIFIC);
+    let style = if positional_len > exponential_len {
+        SCIENTIFIC
+    } else {
+        POSITIONAL
+    };
+    dtoa::prettify(buffer, length, k, style)
+}
+
+// Rewrites the output in the given exponent style and digits. Only the digits
+// of the mantissa and exponent are transliterated, not the base 10 of the LaTeX
+// and Unicode styles.
+unsafe fn restyle(
+    start: *mut u8,
+    end: *mut u8,
+    exponent_style: ExponentStyle,
+    digits: Digits,
+) -> *mut u8 {
+    const SUPERSCRIPT_DIGITS: [&str; 10] = ["⁰", "¹", "²", "³", "⁴", "⁵", "⁶", "⁷", "⁸", "⁹"];
+    const SUPERSCRIPT_MINUS: &str = "⁻";
+
+    let output = slice::from_raw_parts(start, end as usize - start as usize);
+    let Some(e) = output.iter().position(|&byte| byte == b'e') else {
+        return transliterate(start, end, digits);
+    };
+    // At most "-324". Copied out since the mantissa grows over it.
+    let mut exponent = [0u8; 4];
+    let exponent = &mut exponent[..output.len() - e - 1];
+    exponent.copy_from_slice(&output[e + 1..]);
+
+    let mut buffer = transliterate(start, start.add(e), digits);
+    match exponent_style {
+        ExponentStyle::Plain => {
+            *buffer = b'e';
+            write_digits(buffer.add(1), exponent, digits)
+        }
+        ExponentStyle::Latex => {
+            buffer = write_str(buffer, " \\times 10^{");
+            buffer = write_digits(buffer, exponent, digits);
+            write_str(buffer, "}")
+        }
+        ExponentStyle::Unicode => {
+            buffer = write_str(buffer, "×10");
+            for &byte in &*exponent {
+                buffer = write_str(
+                    buffer,
+                    if byte == b'-' {
+                        SUPERSCRIPT_MINUS
+                    } else {
+                        SUPERSCRIPT_DIGITS[(byte - b'0') as usize]
+                    },
+                );
+            }
+            buffer
+        }
+    }
+}
+
+// Replaces the ASCII digits in the output with the given digits, working back
+// from the end since the output grows.
+unsafe fn transliterate(start: *mut u8, end: *mut u8, digits: Digits) -> *mut u8 {
+    let encodings = digits.encodings();
+    let width = encodings[0].len();
+    let len = end as usize - start as usize;
+    let count = slice::from_raw_parts(start, len)
+        .iter()
+        .filter(|byte| byte.is_ascii_digit())
+        .count();
+
+    let new_end = end.add(count * (width - 1));
+    let mut src = end;
+    let mut dst = new_end;
+    while src > start {
+        src = src.sub(1);
+        let byte = *src;
+        if byte.is_ascii_digit() {
+            let encoding = encodings[(byte - b'0') as usize];
+            dst = dst.sub(width);
+            ptr::copy_nonoverlapping(encoding.as_ptr(), dst, width);
+        } else {
+            dst = dst.sub(1);
+            *dst = byte;
+        }
+    }
+    new_end
+}
+
+// Writes ASCII text with its digits replaced by the given digits.
+unsafe fn write_digits(mut buffer: *mut u8, ascii: &[u8], digits: Digits) -> *mut u8 {
+    let encodings = digits.encodings();
+    for &byte in ascii {
+        if byte.is_ascii_digit() {
+            buffer = write_str(buffer, encodings[(byte - b'0') as usize]);
+        } else {
+            *buffer = byte;
+            buffer = buffer.add(1);
+        }
+    }
+    buffer
+}
+
+unsafe fn write_str(buffer: *mut u8, s: &str) -> *mut u8 {
+    ptr::copy_nonoverlapping(s.as_ptr(), buffer, s.len());
+    buffer.add(s.len())
+}
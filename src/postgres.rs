@@ -0,0 +1,54 @@
+use crate::{exact, format_nonfinite, layout, write_signed, Buffer, Float};
+use core::str;
+
+const NAN: &str = "NaN";
+const INFINITY: &str = "Infinity";
+const NEG_INFINITY: &str = "-Infinity";
+
+impl Buffer {
+    /// Print a floating point number the way PostgreSQL 12 and later print
+    /// `float8` for an `f64`, or `float4` for an `f32`, with the default
+    /// `extra_float_digits` of 1.
+    ///
+    /// The output uses the shortest digits that round trip, choosing the
+    /// closest if there are several. Like PostgreSQL, digits exactly halfway
+    /// to a neighboring float are never chosen, even though they parse to the
+    /// value when its significand is even. Exponential notation is used if the
+    /// decimal exponent is less than -4 or at least 15 for `float8`, 6 for
+    /// `float4`, with a sign and at least two digits in the exponent: "1e+16",
+    /// "1.5e-05". Otherwise the number is positional without ".0" on integral
+    /// values: "100", "0.0001".
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "NaN", positive infinity as "Infinity", negative
+    /// infinity as "-Infinity", and negative zero as "-0".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::Buffer::new();
+    /// assert_eq!(buffer.format_postgres(1e16), "1e+16");
+    /// assert_eq!(buffer.format_postgres(100.0), "100");
+    /// assert_eq!(buffer.format_postgres(1e6f32), "1e+06");
+    /// ```
+    pub fn format_postgres<F: Float>(&mut self, value: F) -> &str {
+        if value.is_nonfinite() {
+            return format_nonfinite(value, NAN, INFINITY, NEG_INFINITY);
+        }
+
+        let mut digits = [0u8; exact::SHORTEST_DIGITS];
+        let (length, k) = exact::shortest_inside(value, &mut digits);
+        unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                let exp = length + k - 1;
+                if exp < -4 || exp >= F::DIGITS {
+                    let buffer = layout::write_mantissa(buffer, &digits, length, length);
+                    layout::write_exponent(buffer, b'e', exp, true, true)
+                } else {
+                    layout::write_fixed(buffer, &digits, length, k, (-k).max(0))
+                }
+            })
+        }
+    }
+}
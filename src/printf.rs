@@ -0,0 +1,325 @@
+//! C `printf`-style formatting of `%e`, `%f` and `%g` conversions.
+//!
+//! The output matches glibc, including its round-half-even rounding of the
+//! exact binary value, for every `f64`.
+//!
+//! ```
+//! let spec = dtoa::printf::Spec::parse("%.3g").unwrap();
+//! assert_eq!(spec.display(1234.5).to_string(), "1.23e+03");
+//!
+//! let spec = dtoa::printf::Spec::parse("%+08.2f").unwrap();
+//! assert_eq!(spec.display(-1.005).to_string(), "-0001.00");
+//! ```
+
+use crate::exact::{self, Rounding};
+use crate::private::Sealed;
+use core::fmt::{self, Write};
+
+const ZEROS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+const SPACES: &str = "                                                                ";
+
+/// A parsed `printf` conversion specification for a floating point argument,
+/// such as `"%.3g"` or `"%-+12.4e"`.
+///
+/// The supported syntax is `%`, any of the flags `-`, `+`, space, `#`, `0`, an
+/// optional width, an optional `.` followed by a precision, an optional `l` or
+/// `L` length modifier which is ignored, and one of the conversions `e`, `E`,
+/// `f`, `F`, `g`, `G`. As in glibc, the width and precision must fit in a C
+/// `int`, so at most 2147483647.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    alternate: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    conversion: u8,
+}
+
+/// An error returned when parsing a [`Spec`] fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    _private: (),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("invalid printf conversion specification")
+    }
+}
+
+impl Spec {
+    /// Parse a conversion specification like `"%.3g"`.
+    pub fn parse(spec: &str) -> Result<Spec, ParseError> {
+        let error = ParseError { _private: () };
+        let mut bytes = spec.as_bytes();
+        match bytes.split_first() {
+            Some((b'%', rest)) => bytes = rest,
+            _ => return Err(error),
+        }
+
+        let mut result = Spec {
+            left: false,
+            plus: false,
+            space: false,
+            alternate: false,
+            zero: false,
+            width: 0,
+            precision: None,
+            conversion: 0,
+        };
+        while let Some((&flag, rest)) = bytes.split_first() {
+            match flag {
+                b'-' => result.left = true,
+                b'+' => result.plus = true,
+                b' ' => result.space = true,
+                b'#' => result.alternate = true,
+                b'0' => result.zero = true,
+                _ => break,
+            }
+            bytes = rest;
+        }
+        result.width = parse_number(&mut bytes).ok_or(error)?;
+        if let Some((b'.', rest)) = bytes.split_first() {
+            bytes = rest;
+            result.precision = Some(parse_number(&mut bytes).ok_or(error)?);
+        }
+        if let Some((b'l' | b'L', rest)) = bytes.split_first() {
+            bytes = rest;
+        }
+        match bytes {
+            [conversion @ (b'e' | b'E' | b'f' | b'F' | b'g' | b'G')] => {
+                result.conversion = *conversion;
+                Ok(result)
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// Return an object that formats `value` according to this specification
+    /// when displayed.
+    pub fn display(&self, value: f64) -> Display {
+        Display { spec: *self, value }
+    }
+}
+
+/// Helper struct for formatting a float with a [`Spec`] using `{}`.
+///
+/// Returned by [`Spec::display`].
+#[derive(Copy, Clone, Debug)]
+pub struct Display {
+    spec: Spec,
+    value: f64,
+}
+
+impl fmt::Display for Display {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let spec = &self.spec;
+        let value = self.value;
+        let sign = if value.is_sign_negative() {
+            "-"
+        } else if spec.plus {
+            "+"
+        } else if spec.space {
+            " "
+        } else {
+            ""
+        };
+
+        if value.is_nonfinite() {
+            let uppercase = spec.conversion.is_ascii_uppercase();
+            let body = match (value.is_nan(), uppercase) {
+                (true, false) => "nan",
+                (true, true) => "NAN",
+                (false, false) => "inf",
+                (false, true) => "INF",
+            };
+            let len = sign.len() + body.len();
+            if !spec.left {
+                write_repeated(formatter, SPACES, spec.width.saturating_sub(len))?;
+            }
+            formatter.write_str(sign)?;
+            formatter.write_str(body)?;
+            if spec.left {
+                write_repeated(formatter, SPACES, spec.width.saturating_sub(len))?;
+            }
+            return Ok(());
+        }
+
+        let mut digits = [0u8; exact::MAX_DIGITS];
+        let number = Number::new(spec, value, &mut digits);
+        let mut counter = Counter(0);
+        number.write(&mut counter)?;
+        let padding = spec.width.saturating_sub(sign.len() + counter.0);
+
+        if spec.left {
+            formatter.write_str(sign)?;
+            number.write(formatter)?;
+            write_repeated(formatter, SPACES, padding)
+        } else if spec.zero {
+            formatter.write_str(sign)?;
+            write_repeated(formatter, ZEROS, padding)?;
+            number.write(formatter)
+        } else {
+            write_repeated(formatter, SPACES, padding)?;
+            formatter.write_str(sign)?;
+            number.write(formatter)
+        }
+    }
+}
+
+// The magnitude of the value, rounded and laid out, without sign or padding.
+struct Number<'a> {
+    digits: &'a [u8],
+    length: isize,
+    k: isize,
+    exponent: Option<isize>,
+    fraction: isize,
+    point: bool,
+    uppercase: bool,
+}
+
+impl<'a> Number<'a> {
+    fn new(spec: &Spec, value: f64, digits: &'a mut [u8; exact::MAX_DIGITS]) -> Self {
+        let (f, e) = value.decode();
+        let (length, k) = exact::exact(f, e, digits);
+        let precision = spec.precision.unwrap_or(6) as isize;
+
+        let (length, k, exponent, fraction) = match spec.conversion.to_ascii_lowercase() {
+            b'f' => {
+                let keep = length + k + precision;
+                let (length, k) = exact::round(digits, length, k, keep, Rounding::HalfEven);
+                (length, k, None, precision)
+            }
+            b'e' => {
+                let (length, k) =
+                    exact::round(digits, length, k, precision + 1, Rounding::HalfEven);
+                (length, k, Some(exponent(f, length, k)), precision)
+            }
+            _ => {
+                let precision = precision.max(1);
+                let unrounded = exponent(f, length, k);
+                let (length, k) = exact::round(digits, length, k, precision, Rounding::HalfEven);
+                let x = exponent(f, length, k);
+                if precision > x && x >= -4 {
+                    let mut fraction = precision - 1 - x;
+                    if !spec.alternate {
+                        fraction = fraction.min((-k).max(0));
+                    }
+                    (length, k, None, fraction)
+                } else {
+                    let mut fraction = precision - 1;
+                    if !spec.alternate {
+                        fraction = fraction.min(length - 1);
+                    } else if precision > unrounded && unrounded >= -4 {
+                        // When rounding carries into a new digit that moves
+                        // the value out of fixed notation, glibc prints "1.e+06"
+                        // rather than "1.00000e+06" for `%#g`.
+                        fraction = 0;
+                    }
+                    (length, k, Some(x), fraction)
+                }
+            }
+        };
+
+        Number {
+            digits: &digits[..],
+            length,
+            k,
+            exponent,
+            fraction,
+            point: fraction > 0 || spec.alternate,
+            uppercase: spec.conversion.is_ascii_uppercase(),
+        }
+    }
+
+    fn write(&self, out: &mut impl Write) -> fmt::Result {
+        match self.exponent {
+            None => {
+                let kk = if self.length == 0 {
+                    0
+                } else {
+                    self.length + self.k
+                };
+                if kk <= 0 {
+                    out.write_char('0')?;
+                } else {
+                    self.write_digits(out, 0, kk)?;
+                }
+                if self.point {
+                    out.write_char('.')?;
+                }
+                self.write_digits(out, kk, kk + self.fraction)
+            }
+            Some(exponent) => {
+                self.write_digits(out, 0, 1)?;
+                if self.point {
+                    out.write_char('.')?;
+                }
+                self.write_digits(out, 1, 1 + self.fraction)?;
+                out.write_char(if self.uppercase { 'E' } else { 'e' })?;
+                out.write_char(if exponent < 0 { '-' } else { '+' })?;
+                let exponent = exponent.unsigned_abs();
+                if exponent < 10 {
+                    out.write_char('0')?;
+                }
+                write!(out, "{exponent}")
+            }
+        }
+    }
+
+    // Writes digits `from..to`, where positions outside the digits are zeros.
+    fn write_digits(&self, out: &mut impl Write, from: isize, to: isize) -> fmt::Result {
+        let start = from.clamp(0, self.length);
+        let end = to.clamp(start, self.length);
+        write_repeated(out, ZEROS, (start.min(to) - from).max(0) as usize)?;
+        // The digits are ASCII.
+        for &digit in &self.digits[start as usize..end as usize] {
+            out.write_char(digit as char)?;
+        }
+        write_repeated(out, ZEROS, (to - end.max(from)).max(0) as usize)
+    }
+}
+
+// Decimal exponent of the first digit, counting zero as having exponent 0.
+fn exponent(f: u64, length: isize, k: isize) -> isize {
+    if f == 0 {
+        0
+    } else {
+        length + k - 1
+    }
+}
+
+// Parses a width or precision, which glibc rejects above `INT_MAX`. This also
+// keeps the digit positions computed from the precision far from overflow.
+fn parse_number(bytes: &mut &[u8]) -> Option<usize> {
+    let mut n = 0u32;
+    while let Some((&digit @ b'0'..=b'9', rest)) = bytes.split_first() {
+        n = n.checked_mul(10)?.checked_add(u32::from(digit - b'0'))?;
+        if n > i32::MAX as u32 {
+            return None;
+        }
+        *bytes = rest;
+    }
+    Some(n as usize)
+}
+
+fn write_repeated(out: &mut impl Write, chunk: &str, mut n: usize) -> fmt::Result {
+    while n > chunk.len() {
+        out.write_str(chunk)?;
+        n -= chunk.len();
+    }
+    out.write_str(&chunk[..n])
+}
+
+struct Counter(usize);
+
+impl Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{exact, format_nonfinite, layout, write_signed, Buffer, Float};
+use core::str;
+
+const NAN: &str = "nan";
+const INFINITY: &str = "inf";
+const NEG_INFINITY: &str = "-inf";
+
+impl Buffer {
+    /// Print a floating point number the way Python's `repr(float)` does.
+    ///
+    /// The output uses the shortest digits that round trip, choosing the
+    /// closest if there are several. Exponential notation is used if the
+    /// decimal exponent is less than -4 or at least 16, with a sign and at
+    /// least two digits in the exponent: "1e+16", "1e-05". Otherwise the number
+    /// is positional, with ".0" appended to integral values: "100.0".
+    ///
+    /// An `f32` is printed with the shortest digits that round trip as `f32`.
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "nan" regardless of its sign bit, positive infinity
+    /// as "inf", and negative infinity as "-inf".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::Buffer::new();
+    /// assert_eq!(buffer.format_python(1e16), "1e+16");
+    /// assert_eq!(buffer.format_python(0.00001), "1e-05");
+    /// assert_eq!(buffer.format_python(100.0), "100.0");
+    /// ```
+    pub fn format_python<F: Float>(&mut self, value: F) -> &str {
+        if value.is_nonfinite() {
+            return format_nonfinite(value, NAN, INFINITY, NEG_INFINITY);
+        }
+
+        let mut digits = [0u8; exact::SHORTEST_DIGITS];
+        let (length, k) = exact::shortest(value, &mut digits);
+        unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                // Python's decpt, the position of the decimal point relative to
+                // the first digit.
+                let decpt = length + k;
+                if decpt <= -4 || decpt > 16 {
+                    let buffer = layout::write_mantissa(buffer, &digits, length, length);
+                    layout::write_exponent(buffer, b'e', decpt - 1, true, true)
+                } else {
+                    layout::write_fixed(buffer, &digits, length, k, (-k).max(1))
+                }
+            })
+        }
+    }
+}
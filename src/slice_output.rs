@@ -0,0 +1,78 @@
+use crate::{dtoa, Buffer, Float};
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// An error returned by [`format_to_slice`] when the output does not fit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    _private: (),
+}
+
+impl fmt::Display for BufferTooSmall {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("buffer too small for formatted float")
+    }
+}
+
+/// Print a floating point number into the front of a caller-provided slice
+/// and return the number of bytes written, which are initialized afterward.
+///
+/// The output is the same as that of [`Buffer::format`]. If the slice is at
+/// least [`Float::MAX_LEN`] long, the number is written directly into it;
+/// otherwise it is formatted on the stack and copied if it fits.
+///
+/// # Errors
+///
+/// Returns an error without writing anything if the output is longer than the
+/// slice.
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use dtoa::Float;
+///
+/// let mut frame = [MaybeUninit::<u8>::uninit(); f64::MAX_LEN];
+/// let len = dtoa::format_to_slice(1.5, &mut frame).unwrap();
+/// let printed: Vec<u8> = frame[..len]
+///     .iter()
+///     .map(|byte| unsafe { byte.assume_init() })
+///     .collect();
+/// assert_eq!(printed, b"1.5");
+/// ```
+pub fn format_to_slice<F: Float>(
+    value: F,
+    slice: &mut [MaybeUninit<u8>],
+) -> Result<usize, BufferTooSmall> {
+    if slice.len() >= F::MAX_LEN {
+        return Ok(unsafe { format_raw(value, slice.as_mut_ptr().cast::<u8>()) });
+    }
+
+    let mut buffer = Buffer::new();
+    let printed = buffer.format(value);
+    if printed.len() > slice.len() {
+        return Err(BufferTooSmall { _private: () });
+    }
+    unsafe {
+        ptr::copy_nonoverlapping(
+            printed.as_ptr(),
+            slice.as_mut_ptr().cast::<u8>(),
+            printed.len(),
+        );
+    }
+    Ok(printed.len())
+}
+
+// Writes the same output as `Buffer::format` and returns its length. There
+// must be room for `F::MAX_LEN` bytes.
+pub unsafe fn format_raw<F: Float>(value: F, start: *mut u8) -> usize {
+    if value.is_nonfinite() {
+        let printed = value.format_nonfinite();
+        ptr::copy_nonoverlapping(printed.as_ptr(), start, printed.len());
+        printed.len()
+    } else {
+        let end = dtoa::dtoa(value, start);
+        end.offset_from(start) as usize
+    }
+}
@@ -0,0 +1,142 @@
+//! Float literals for generated SQL text.
+//!
+//! Numbers are printed as approximate numeric literals with a mantissa and an
+//! exponent, like `1.5E10`, which SQL reads as a floating point value rather
+//! than an exact decimal. NaN and infinities have no literal in standard SQL;
+//! the dialects that support them get their own syntax and the rest get an
+//! error.
+//!
+//! ```
+//! use dtoa::sql::Dialect;
+//!
+//! let mut buffer = dtoa::Buffer::new();
+//! assert_eq!(buffer.format_sql(1.5e10, Dialect::Standard), Ok("1.5E10"));
+//! assert_eq!(
+//!     buffer.format_sql(f64::INFINITY, Dialect::PostgreSql),
+//!     Ok("CAST('Infinity' AS DOUBLE PRECISION)"),
+//! );
+//! assert!(buffer.format_sql(f64::NAN, Dialect::MySql).is_err());
+//! ```
+
+use crate::{layout, write_signed, Buffer, Float};
+use core::{fmt, mem, str};
+
+/// A SQL dialect, which determines how NaN and infinities are written.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// ISO SQL, which has no literal for NaN or infinity.
+    Standard,
+    /// PostgreSQL, which writes NaN and infinities as casts of the strings
+    /// 'NaN', 'Infinity' and '-Infinity' to `DOUBLE PRECISION`, or to `REAL`
+    /// for an `f32`. Note that PostgreSQL gives an exponent literal the type
+    /// `numeric` until it is converted to the target type, which is exact for
+    /// the digits printed here.
+    PostgreSql,
+    /// MySQL and MariaDB, which have no literal for NaN or infinity.
+    MySql,
+    /// Microsoft SQL Server, which has no literal for NaN or infinity.
+    SqlServer,
+    /// SQLite, which reads the out of range literals `9e999` and `-9e999` as
+    /// infinities and has no NaN.
+    Sqlite,
+    /// Oracle, whose literals have a `D` suffix for `BINARY_DOUBLE` or `F` for
+    /// `BINARY_FLOAT`, and which has the constants `BINARY_DOUBLE_NAN` and
+    /// `BINARY_DOUBLE_INFINITY`.
+    Oracle,
+}
+
+/// An error returned when a value has no literal in the requested dialect.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    _private: (),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("SQL dialect has no literal for NaN or infinity")
+    }
+}
+
+impl Buffer {
+    /// Print a floating point number as a SQL approximate numeric literal in
+    /// the given dialect.
+    ///
+    /// The digits are the shortest that round trip, as printed by
+    /// [`format_finite`][Buffer::format_finite], laid out as a mantissa with
+    /// one digit before the decimal point and an exponent: "1.5E10", "1E0",
+    /// "-2.5E-7".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for NaN and infinities if the dialect has no way to
+    /// write them. See [`Dialect`].
+    pub fn format_sql<F: Float>(&mut self, value: F, dialect: Dialect) -> Result<&str, Error> {
+        let single = mem::size_of::<F>() == 4;
+        if value.is_nonfinite() {
+            return format_nonfinite(value, dialect, single);
+        }
+
+        Ok(unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                // Leave room for the mantissa's decimal point after the first
+                // digit.
+                let (length, k) = value.grisu(buffer.add(1));
+                *buffer = *buffer.add(1);
+                let end = if length > 1 {
+                    *buffer.add(1) = b'.';
+                    buffer.offset(length + 1)
+                } else {
+                    buffer.add(1)
+                };
+                let mut end = layout::write_exponent(end, b'E', length + k - 1, false, false);
+                if dialect == Dialect::Oracle {
+                    *end = if single { b'F' } else { b'D' };
+                    end = end.add(1);
+                }
+                end
+            })
+        })
+    }
+}
+
+#[cold]
+fn format_nonfinite<F: Float>(
+    value: F,
+    dialect: Dialect,
+    single: bool,
+) -> Result<&'static str, Error> {
+    let nan = value.is_nan();
+    let negative = value.is_sign_negative();
+    match (dialect, single) {
+        (Dialect::PostgreSql, false) => Ok(if nan {
+            "CAST('NaN' AS DOUBLE PRECISION)"
+        } else if negative {
+            "CAST('-Infinity' AS DOUBLE PRECISION)"
+        } else {
+            "CAST('Infinity' AS DOUBLE PRECISION)"
+        }),
+        (Dialect::PostgreSql, true) => Ok(if nan {
+            "CAST('NaN' AS REAL)"
+        } else if negative {
+            "CAST('-Infinity' AS REAL)"
+        } else {
+            "CAST('Infinity' AS REAL)"
+        }),
+        (Dialect::Sqlite, _) if !nan => Ok(if negative { "-9e999" } else { "9e999" }),
+        (Dialect::Oracle, false) => Ok(if nan {
+            "BINARY_DOUBLE_NAN"
+        } else if negative {
+            "-BINARY_DOUBLE_INFINITY"
+        } else {
+            "BINARY_DOUBLE_INFINITY"
+        }),
+        (Dialect::Oracle, true) => Ok(if nan {
+            "BINARY_FLOAT_NAN"
+        } else if negative {
+            "-BINARY_FLOAT_INFINITY"
+        } else {
+            "BINARY_FLOAT_INFINITY"
+        }),
+        _ => Err(Error { _private: () }),
+    }
+}
@@ -0,0 +1,167 @@
+//! Compact number formatting for SVG path data and other SVG attributes.
+//!
+//! The SVG number grammar allows `.5` for `0.5`, omits `.0` on integers, and
+//! lets a `-` or a second `.` start a new number without a separator, which
+//! this module takes advantage of to produce the shortest text.
+//!
+//! ```
+//! let mut buffer = dtoa::Buffer::new();
+//! assert_eq!(buffer.format_svg(-0.5, 3), Some("-.5"));
+//! assert_eq!(buffer.format_svg(3.14159, 2), Some("3.14"));
+//! assert_eq!(buffer.format_svg(120000.0, 2), Some("12e4"));
+//!
+//! let path = dtoa::svg::join(&[10.0, -0.5, 0.25, 0.75], 2);
+//! assert_eq!(path.to_string(), "10-.5.25.75");
+//! ```
+
+use crate::exact::{self, Rounding};
+use crate::{dtoa, layout, write_signed, Buffer, Float};
+use core::fmt;
+use core::str;
+
+impl Buffer {
+    /// Print a floating point number as the shortest SVG number token with at
+    /// most `max_decimals` digits after the decimal point.
+    ///
+    /// The value is printed with the shortest digits that round trip, unless
+    /// those need more than `max_decimals` decimals, in which case the exact
+    /// value is rounded to `max_decimals` decimals with ties to even. The token
+    /// has no leading zero before the decimal point, no trailing zeros after it,
+    /// and uses an exponent only if that is strictly shorter: "-.5", "100",
+    /// "12e4", "1e-7". A value that rounds to zero is printed as "0" without a
+    /// sign.
+    ///
+    /// Returns `None` for NaN and infinities, which SVG cannot represent.
+    pub fn format_svg<F: Float>(&mut self, value: F, max_decimals: usize) -> Option<&str> {
+        if value.is_nonfinite() {
+            return None;
+        }
+
+        // No float has more than 1074 decimals, so this does not change the
+        // result.
+        let max_decimals = max_decimals.min(1100) as isize;
+        let mut digits = [0u8; exact::SHORTEST_DIGITS];
+        let (mut length, mut k) = exact::shortest(value, &mut digits);
+        if -k > max_decimals {
+            (length, k) = round_to_decimals(value, max_decimals, &mut digits);
+        }
+        if length == 0 || digits[0] == b'0' {
+            return Some("0");
+        }
+
+        Some(unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                if length + layout::exponent_len(k) < positional_len(length, k) {
+                    let buffer = write_digits(buffer, &digits[..length as usize]);
+                    layout::write_exponent(buffer, b'e', k, false, false)
+                } else {
+                    write_positional(buffer, &digits, length, k)
+                }
+            })
+        })
+    }
+}
+
+/// Format a sequence of numbers as by [`Buffer::format_svg`], separated by a
+/// space only where the next number does not already start a new token.
+///
+/// A number starting with `-` needs no separator, and neither does a number
+/// starting with `.` if the previous number contains a `.` or an exponent.
+///
+/// # Errors
+///
+/// Displaying the result fails with [`fmt::Error`] if any of the values is
+/// NaN or infinite.
+pub fn join<F: Float>(values: &[F], max_decimals: usize) -> Join<'_, F> {
+    Join {
+        values,
+        max_decimals,
+    }
+}
+
+/// Helper struct for formatting a sequence of SVG numbers using `{}`.
+///
+/// Returned by [`join`].
+#[derive(Copy, Clone, Debug)]
+pub struct Join<'a, F> {
+    values: &'a [F],
+    max_decimals: usize,
+}
+
+impl<F: Float> fmt::Display for Join<'_, F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut buffer = Buffer::new();
+        let mut first = true;
+        let mut previous_has_point = false;
+        for &value in self.values {
+            let token = buffer
+                .format_svg(value, self.max_decimals)
+                .ok_or(fmt::Error)?;
+            let delimited =
+                token.starts_with('-') || (token.starts_with('.') && previous_has_point);
+            if !first && !delimited {
+                formatter.write_str(" ")?;
+            }
+            formatter.write_str(token)?;
+            first = false;
+            previous_has_point = token.contains(['.', 'e']);
+        }
+        Ok(())
+    }
+}
+
+// Rounds the exact value to `max_decimals` decimals. That is fewer than the
+// shortest digits have, so the result is no longer than they are.
+fn round_to_decimals<F: Float>(
+    value: F,
+    max_decimals: isize,
+    digits: &mut [u8; exact::SHORTEST_DIGITS],
+) -> (isize, isize) {
+    let mut exact_digits = [0u8; exact::MAX_DIGITS];
+    let (f, e) = value.decode();
+    let (length, k) = exact::exact(f, e, &mut exact_digits);
+    let keep = length + k + max_decimals;
+    let (length, k) = exact::round(&mut exact_digits, length, k, keep, Rounding::HalfEven);
+    digits[..length as usize].copy_from_slice(&exact_digits[..length as usize]);
+    (length, k)
+}
+
+// Length without sign of the positional form, like "1500" or ".0015", which
+// has no zero before the decimal point.
+fn positional_len(length: isize, k: isize) -> isize {
+    dtoa::positional_len(length, k) - isize::from(length + k <= 0)
+}
+
+unsafe fn write_positional(buffer: *mut u8, digits: &[u8], length: isize, k: isize) -> *mut u8 {
+    let kk = length + k;
+    let digits = &digits[..length as usize];
+    if k >= 0 {
+        // 15e2 -> 1500
+        let buffer = write_digits(buffer, digits);
+        write_repeated(buffer, b'0', k)
+    } else if kk > 0 {
+        // 15e-1 -> 1.5
+        let (integer, fraction) = digits.split_at(kk as usize);
+        let buffer = write_digits(buffer, integer);
+        *buffer = b'.';
+        write_digits(buffer.add(1), fraction)
+    } else {
+        // 15e-4 -> .0015
+        *buffer = b'.';
+        let buffer = write_repeated(buffer.add(1), b'0', -kk);
+        write_digits(buffer, digits)
+    }
+}
+
+unsafe fn write_digits(buffer: *mut u8, digits: &[u8]) -> *mut u8 {
+    buffer.copy_from_nonoverlapping(digits.as_ptr(), digits.len());
+    buffer.add(digits.len())
+}
+
+unsafe fn write_repeated(mut buffer: *mut u8, byte: u8, n: isize) -> *mut u8 {
+    for _ in 0..n {
+        *buffer = byte;
+        buffer = buffer.add(1);
+    }
+    buffer
+}
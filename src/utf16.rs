@@ -0,0 +1,105 @@
+use crate::{dtoa, Float};
+use core::mem::MaybeUninit;
+use core::slice;
+#[cfg(feature = "no-panic")]
+use no_panic::no_panic;
+
+/// Like [`Buffer`][crate::Buffer] but producing UTF-16 code units, for
+/// embedders whose strings are UTF-16 such as JavaScript engines and Windows.
+///
+/// The output is the same as that of `Buffer`, with each byte widened to a
+/// `u16`.
+///
+/// # Example
+///
+/// ```
+/// let mut buffer = dtoa::Buffer16::new();
+/// let printed = buffer.format_utf16(1.234);
+/// assert_eq!(String::from_utf16(printed).unwrap(), "1.234");
+/// ```
+pub struct Buffer16 {
+    units: [MaybeUninit<u16>; 25],
+}
+
+impl Default for Buffer16 {
+    #[inline]
+    fn default() -> Buffer16 {
+        Buffer16::new()
+    }
+}
+
+impl Copy for Buffer16 {}
+
+#[allow(clippy::non_canonical_clone_impl)]
+impl Clone for Buffer16 {
+    #[inline]
+    fn clone(&self) -> Self {
+        Buffer16::new()
+    }
+}
+
+impl Buffer16 {
+    /// This is a cheap operation; you don't need to worry about reusing buffers
+    /// for efficiency.
+    #[inline]
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    pub fn new() -> Buffer16 {
+        let units = [MaybeUninit::<u16>::uninit(); 25];
+        Buffer16 { units }
+    }
+
+    /// Print a floating point number into this buffer and return a reference to
+    /// its UTF-16 representation within the buffer.
+    ///
+    /// # Special cases
+    ///
+    /// This function formats NaN as the string "NaN", positive infinity as
+    /// "inf", and negative infinity as "-inf" to match std::fmt.
+    ///
+    /// If your input is known to be finite, you may get better performance by
+    /// calling the `format_utf16_finite` method instead of `format_utf16` to
+    /// avoid the checks for special cases.
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    pub fn format_utf16<F: Float>(&mut self, value: F) -> &[u16] {
+        if value.is_nonfinite() {
+            let string = value.format_nonfinite();
+            for (unit, &byte) in self.units.iter_mut().zip(string.as_bytes()) {
+                *unit = MaybeUninit::new(u16::from(byte));
+            }
+            unsafe { slice::from_raw_parts(self.units.as_ptr().cast::<u16>(), string.len()) }
+        } else {
+            self.format_utf16_finite(value)
+        }
+    }
+
+    /// Print a floating point number into this buffer and return a reference to
+    /// its UTF-16 representation within the buffer.
+    ///
+    /// # Special cases
+    ///
+    /// This function **does not** check for NaN or infinity. If the input
+    /// number is not a finite float, the printed representation will be some
+    /// correctly formatted but unspecified numerical value.
+    #[cfg_attr(feature = "no-panic", no_panic)]
+    pub fn format_utf16_finite<F: Float>(&mut self, value: F) -> &[u16] {
+        unsafe {
+            let start = self.units.as_mut_ptr().cast::<u16>();
+            let mut buffer = start;
+            if value.is_sign_negative() {
+                *buffer = u16::from(b'-');
+                buffer = buffer.add(1);
+            }
+            // Digits are generated as bytes and widened before the shared
+            // layout step rearranges them.
+            let mut digits = [MaybeUninit::<u8>::uninit(); 24];
+            let digits = digits.as_mut_ptr().cast::<u8>();
+            let (length, k) = value.grisu(digits);
+            for i in 0..length {
+                *buffer.offset(i) = u16::from(*digits.offset(i));
+            }
+            let end = dtoa::prettify(buffer, length, k, dtoa::STANDARD);
+            let len = end.offset_from(start) as usize;
+            slice::from_raw_parts(start, len)
+        }
+    }
+}
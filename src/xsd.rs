@@ -0,0 +1,90 @@
+use crate::{format_nonfinite, layout, write_signed, Buffer, Float, LargeBuffer};
+use core::str;
+
+const NAN: &str = "NaN";
+const INFINITY: &str = "INF";
+const NEG_INFINITY: &str = "-INF";
+
+impl Buffer {
+    /// Print a floating point number in the canonical lexical representation
+    /// of XML Schema 1.1 `xsd:double`, or `xsd:float` for an `f32`.
+    ///
+    /// The output uses the shortest digits that round trip, as a mantissa with
+    /// one nonzero digit before the decimal point and at least one after it,
+    /// followed by "E" and the exponent without a plus sign or leading zeros:
+    /// "1.0E0", "-1.5E-3", "1.234E10".
+    ///
+    /// # Special cases
+    ///
+    /// NaN is formatted as "NaN", positive infinity as "INF", negative infinity
+    /// as "-INF", zero as "0.0E0" and negative zero as "-0.0E0".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::Buffer::new();
+    /// assert_eq!(buffer.format_xsd(1.0), "1.0E0");
+    /// assert_eq!(buffer.format_xsd(-0.0015), "-1.5E-3");
+    /// assert_eq!(buffer.format_xsd(f64::INFINITY), "INF");
+    /// ```
+    pub fn format_xsd<F: Float>(&mut self, value: F) -> &str {
+        if value.is_nonfinite() {
+            return format_nonfinite(value, NAN, INFINITY, NEG_INFINITY);
+        }
+
+        let mut digits = [0u8; 32];
+        let (length, k) = shortest(value, &mut digits);
+        unsafe {
+            write_signed(&mut self.bytes, value.is_sign_negative(), |buffer| {
+                let buffer = layout::write_mantissa(buffer, &digits, length, length.max(2));
+                layout::write_exponent(buffer, b'E', length + k - 1, false, false)
+            })
+        }
+    }
+}
+
+impl LargeBuffer {
+    /// Print a floating point number in the canonical lexical representation
+    /// of XML Schema 1.1 `xsd:decimal`, which never uses an exponent.
+    ///
+    /// The output uses the shortest digits that round trip, without leading or
+    /// trailing zeros. Integral values have no decimal point: "100", "-1.5",
+    /// "0.001". Zero of either sign is formatted as "0".
+    ///
+    /// Returns `None` for NaN and infinities, which `xsd:decimal` cannot
+    /// represent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut buffer = dtoa::LargeBuffer::new();
+    /// assert_eq!(buffer.format_xsd_decimal(1e3), Some("1000"));
+    /// assert_eq!(buffer.format_xsd_decimal(-1.5e-3), Some("-0.0015"));
+    /// assert_eq!(buffer.format_xsd_decimal(f64::NAN), None);
+    /// ```
+    pub fn format_xsd_decimal<F: Float>(&mut self, value: F) -> Option<&str> {
+        if value.is_nonfinite() {
+            return None;
+        }
+
+        let mut digits = [0u8; 32];
+        let (length, k) = shortest(value, &mut digits);
+        let negative = value.is_sign_negative() && digits[0] != b'0';
+        Some(unsafe {
+            write_signed(&mut self.bytes, negative, |buffer| {
+                layout::write_fixed(buffer, &digits, length, k, (-k).max(0))
+            })
+        })
+    }
+}
+
+// Shortest digits of the absolute value with trailing zeros removed, since
+// the canonical forms have none. Zero is the single digit "0".
+fn shortest<F: Float>(value: F, digits: &mut [u8; 32]) -> (isize, isize) {
+    let (mut length, mut k) = unsafe { value.grisu(digits.as_mut_ptr()) };
+    while length > 1 && digits[length as usize - 1] == b'0' {
+        length -= 1;
+        k += 1;
+    }
+    (length, k)
+}
@@ -4,7 +4,7 @@
     clippy::unseparated_literal_suffix
 )]
 
-use std::{f32, f64};
+use std::{f32, f64, fmt, mem};
 
 #[test]
 fn test_f64() {
@@ -41,3 +41,843 @@ fn test_write<F: dtoa::Float>(value: F, expected: &'static str) {
     let string = buffer.format(value);
     assert_eq!(string, expected);
 }
+
+#[test]
+fn test_buffer_size() {
+    assert_eq!(mem::size_of::<dtoa::Buffer>(), 25);
+}
+
+#[test]
+fn test_ecma() {
+    let mut buffer = dtoa::LargeBuffer::new();
+    assert_eq!(buffer.to_fixed(1.005f64, 2), "1.00");
+    assert_eq!(buffer.to_fixed(1.45f64, 1), "1.4");
+    assert_eq!(buffer.to_fixed(1.25f64, 1), "1.3");
+    assert_eq!(buffer.to_fixed(0.5f64, 0), "1");
+    assert_eq!(buffer.to_fixed(-0.0001f64, 2), "-0.00");
+    assert_eq!(buffer.to_fixed(-0.0f64, 1), "0.0");
+    assert_eq!(buffer.to_fixed(123.456f64, 10), "123.4560000000");
+    assert_eq!(buffer.to_fixed(1e21f64, 2), "1e+21");
+    assert_eq!(buffer.to_fixed(-1.5e300f64, 2), "-1.5e+300");
+    assert_eq!(buffer.to_fixed(f64::NAN, 2), "NaN");
+    assert_eq!(buffer.to_fixed(f64::NEG_INFINITY, 2), "-Infinity");
+
+    assert_eq!(buffer.to_exponential(0.0f64, 2), "0.00e+0");
+    assert_eq!(buffer.to_exponential(123456f64, 2), "1.23e+5");
+    assert_eq!(buffer.to_exponential(25f64, 0), "3e+1");
+    assert_eq!(buffer.to_exponential(5e-324f64, 3), "4.941e-324");
+    assert_eq!(buffer.to_exponential(f64::INFINITY, 2), "Infinity");
+
+    assert_eq!(buffer.to_precision(0.0f64, 1), "0");
+    assert_eq!(buffer.to_precision(0.0f64, 3), "0.00");
+    assert_eq!(buffer.to_precision(123.456f64, 4), "123.5");
+    assert_eq!(buffer.to_precision(123456f64, 2), "1.2e+5");
+    assert_eq!(buffer.to_precision(0.000001234f64, 2), "0.0000012");
+    assert_eq!(buffer.to_precision(0.0000001234f64, 2), "1.2e-7");
+    assert_eq!(buffer.to_precision(99.99f64, 3), "100");
+    assert_eq!(buffer.to_precision(2.5f32, 1), "3");
+}
+
+#[test]
+fn test_python() {
+    // Outputs of CPython's repr(float).
+    let cases = [
+        (0.0f64, "0.0"),
+        (-0.0f64, "-0.0"),
+        (1.0f64, "1.0"),
+        (100.0f64, "100.0"),
+        (-1.5f64, "-1.5"),
+        (0.1f64, "0.1"),
+        (0.3f64, "0.3"),
+        (0.0001f64, "0.0001"),
+        (0.00001f64, "1e-05"),
+        (1e16f64, "1e+16"),
+        (9999999999999998.0f64, "9999999999999998.0"),
+        (1.2345678901234566e17f64, "1.2345678901234566e+17"),
+        (123456789012345.6f64, "123456789012345.6"),
+        (9.223372036854776e18f64, "9.223372036854776e+18"),
+        (1e22f64, "1e+22"),
+        (1e23f64, "1e+23"),
+        (-1e-300f64, "-1e-300"),
+        (5e-324f64, "5e-324"),
+        (2.2250738585072014e-308f64, "2.2250738585072014e-308"),
+        (f64::MAX, "1.7976931348623157e+308"),
+        (f64::NAN, "nan"),
+        (-f64::NAN, "nan"),
+        (f64::INFINITY, "inf"),
+        (f64::NEG_INFINITY, "-inf"),
+    ];
+    let mut buffer = dtoa::Buffer::new();
+    for (value, expected) in cases {
+        assert_eq!(buffer.format_python(value), expected);
+    }
+}
+
+#[test]
+fn test_java() {
+    let mut buffer = dtoa::Buffer::new();
+    assert_eq!(buffer.format_java(0.0f64), "0.0");
+    assert_eq!(buffer.format_java(-0.0f64), "-0.0");
+    assert_eq!(buffer.format_java(1.0f64), "1.0");
+    assert_eq!(buffer.format_java(123456.0f64), "123456.0");
+    assert_eq!(buffer.format_java(1234567.5f64), "1234567.5");
+    assert_eq!(buffer.format_java(1e7f64), "1.0E7");
+    assert_eq!(buffer.format_java(0.001f64), "0.001");
+    assert_eq!(buffer.format_java(0.000999f64), "9.99E-4");
+    assert_eq!(buffer.format_java(-1.5e-10f64), "-1.5E-10");
+    assert_eq!(buffer.format_java(2e23f64), "2.0E23");
+    assert_eq!(buffer.format_java(1e23f64), "1.0E23");
+    assert_eq!(buffer.format_java(5e-324f64), "4.9E-324");
+    assert_eq!(buffer.format_java(1e-323f64), "9.9E-324");
+    assert_eq!(buffer.format_java(f64::MAX), "1.7976931348623157E308");
+    assert_eq!(buffer.format_java(f64::NAN), "NaN");
+    assert_eq!(buffer.format_java(f64::INFINITY), "Infinity");
+    assert_eq!(buffer.format_java(f64::NEG_INFINITY), "-Infinity");
+
+    assert_eq!(buffer.format_java(0.1f32), "0.1");
+    assert_eq!(buffer.format_java(1e-45f32), "1.4E-45");
+    assert_eq!(buffer.format_java(f32::MAX), "3.4028235E38");
+    assert_eq!(buffer.format_java(1.0e10f32), "1.0E10");
+}
+
+#[test]
+fn test_printf() {
+    fn printf(spec: &str, value: f64) -> String {
+        dtoa::printf::Spec::parse(spec)
+            .unwrap()
+            .display(value)
+            .to_string()
+    }
+
+    // Outputs of glibc's printf.
+    assert_eq!(printf("%.0f", 1.5), "2");
+    assert_eq!(printf("%.0f", 2.5), "2");
+    assert_eq!(printf("%.2f", 0.125), "0.12");
+    assert_eq!(printf("%f", 1e300).len(), 308);
+    assert_eq!(printf("%g", 999999.5), "1e+06");
+    assert_eq!(printf("%#g", 999999.5), "1.e+06");
+    assert_eq!(printf("%g", 0.00001), "1e-05");
+    assert_eq!(printf("%g", 0.0001), "0.0001");
+    assert_eq!(printf("%.3g", 1234.0), "1.23e+03");
+    assert_eq!(printf("%e", 5e-324), "4.940656e-324");
+    assert_eq!(printf("%.3E", f64::MAX), "1.798E+308");
+    assert_eq!(printf("%+08.2f", -1.5), "-0001.50");
+    assert_eq!(printf("%#.0f", 1.0), "1.");
+    assert_eq!(printf("%#g", 1.0), "1.00000");
+    assert_eq!(printf("%-8.1e", 1.0), "1.0e+00 ");
+    assert_eq!(printf("% 010.3G", 1.0), " 000000001");
+    assert_eq!(printf("%.1e", -0.0), "-0.0e+00");
+    assert_eq!(printf("%08f", f64::NAN), "     nan");
+    assert_eq!(printf("%f", -f64::NAN), "-nan");
+    assert_eq!(printf("%+F", f64::NEG_INFINITY), "-INF");
+
+    assert!(dtoa::printf::Spec::parse("%d").is_err());
+    assert!(dtoa::printf::Spec::parse("%.3gx").is_err());
+    assert!(dtoa::printf::Spec::parse(".3g").is_err());
+    assert!(dtoa::printf::Spec::parse("%lf").is_ok());
+
+    // Like glibc, widths and precisions above INT_MAX are rejected.
+    assert!(dtoa::printf::Spec::parse("%2147483647f").is_ok());
+    assert!(dtoa::printf::Spec::parse("%.2147483647e").is_ok());
+    assert!(dtoa::printf::Spec::parse("%2147483648f").is_err());
+    assert!(dtoa::printf::Spec::parse("%.2147483648g").is_err());
+    assert!(dtoa::printf::Spec::parse("%.9223372036854775808f").is_err());
+    assert!(dtoa::printf::Spec::parse("%.9223372036854775807e").is_err());
+    assert!(dtoa::printf::Spec::parse("%.99999999999999999999999e").is_err());
+}
+
+#[test]
+fn test_go() {
+    use dtoa::GoFormat;
+
+    let mut buffer = dtoa::LargeBuffer::new();
+
+    // Outputs of Go's strconv.FormatFloat with precision -1.
+    assert_eq!(buffer.format_go(1e6, GoFormat::LowerGeneral), "1e+06");
+    assert_eq!(buffer.format_go(123456.0, GoFormat::LowerGeneral), "123456");
+    assert_eq!(
+        buffer.format_go(123456789.0, GoFormat::LowerGeneral),
+        "1.23456789e+08"
+    );
+    assert_eq!(buffer.format_go(0.0001, GoFormat::LowerGeneral), "0.0001");
+    assert_eq!(buffer.format_go(0.00001, GoFormat::UpperGeneral), "1E-05");
+    assert_eq!(buffer.format_go(0.0, GoFormat::LowerGeneral), "0");
+    assert_eq!(buffer.format_go(-0.0, GoFormat::LowerExp), "-0e+00");
+    assert_eq!(buffer.format_go(1.5, GoFormat::LowerExp), "1.5e+00");
+    assert_eq!(
+        buffer.format_go(f64::MAX, GoFormat::UpperExp),
+        "1.7976931348623157E+308"
+    );
+    assert_eq!(
+        buffer.format_go(1e21, GoFormat::Fixed),
+        "1000000000000000000000"
+    );
+    assert_eq!(buffer.format_go(0.000001, GoFormat::Fixed), "0.000001");
+    assert_eq!(buffer.format_go(-5e-324, GoFormat::Fixed).len(), 327);
+    assert_eq!(buffer.format_go(0.1f32, GoFormat::LowerGeneral), "0.1");
+    assert_eq!(
+        buffer.format_go(f64::from(0.1f32), GoFormat::LowerGeneral),
+        "0.10000000149011612"
+    );
+    assert_eq!(
+        buffer.format_go(f32::MAX, GoFormat::LowerGeneral),
+        "3.4028235e+38"
+    );
+    assert_eq!(buffer.format_go(f64::NAN, GoFormat::Fixed), "NaN");
+    assert_eq!(
+        buffer.format_go(f64::INFINITY, GoFormat::LowerGeneral),
+        "+Inf"
+    );
+    assert_eq!(
+        buffer.format_go(f32::NEG_INFINITY, GoFormat::LowerExp),
+        "-Inf"
+    );
+
+    assert_eq!(
+        buffer.format_go(1.0, GoFormat::Binary),
+        "4503599627370496p-52"
+    );
+    assert_eq!(buffer.format_go(-0.0, GoFormat::Binary), "-0p-1074");
+    assert_eq!(
+        buffer.format_go(1e300, GoFormat::Binary),
+        "6724873095247260p+944"
+    );
+    assert_eq!(buffer.format_go(1.0f32, GoFormat::Binary), "8388608p-23");
+    assert_eq!(buffer.format_go(1.5, GoFormat::LowerHex), "0x1.8p+00");
+    assert_eq!(buffer.format_go(-0.0, GoFormat::LowerHex), "-0x0p+00");
+    assert_eq!(
+        buffer.format_go(0.1, GoFormat::UpperHex),
+        "0X1.999999999999AP-04"
+    );
+    assert_eq!(buffer.format_go(5e-324, GoFormat::LowerHex), "0x1p-1074");
+    assert_eq!(
+        buffer.format_go(f64::MAX, GoFormat::LowerHex),
+        "0x1.fffffffffffffp+1023"
+    );
+    assert_eq!(
+        buffer.format_go(0.1f32, GoFormat::LowerHex),
+        "0x1.99999ap-04"
+    );
+}
+
+#[test]
+fn test_xsd() {
+    let mut buffer = dtoa::Buffer::new();
+
+    assert_eq!(buffer.format_xsd(1.0), "1.0E0");
+    assert_eq!(buffer.format_xsd(-1.5e-3), "-1.5E-3");
+    assert_eq!(buffer.format_xsd(1.234e10), "1.234E10");
+    assert_eq!(buffer.format_xsd(100.0), "1.0E2");
+    assert_eq!(buffer.format_xsd(0.0), "0.0E0");
+    assert_eq!(buffer.format_xsd(-0.0), "-0.0E0");
+    assert_eq!(buffer.format_xsd(0.1f32), "1.0E-1");
+    assert_eq!(buffer.format_xsd(f64::MAX), "1.7976931348623157E308");
+    assert_eq!(buffer.format_xsd(f64::NAN), "NaN");
+    assert_eq!(buffer.format_xsd(f64::INFINITY), "INF");
+    assert_eq!(buffer.format_xsd(f32::NEG_INFINITY), "-INF");
+
+    let mut buffer = dtoa::LargeBuffer::new();
+    assert_eq!(buffer.format_xsd_decimal(1.0), Some("1"));
+    assert_eq!(buffer.format_xsd_decimal(-1.5), Some("-1.5"));
+    assert_eq!(
+        buffer.format_xsd_decimal(1e21),
+        Some("1000000000000000000000")
+    );
+    assert_eq!(buffer.format_xsd_decimal(0.001), Some("0.001"));
+    assert_eq!(buffer.format_xsd_decimal(-0.0), Some("0"));
+    assert_eq!(buffer.format_xsd_decimal(0.1f32), Some("0.1"));
+    assert_eq!(buffer.format_xsd_decimal(-5e-324).map(str::len), Some(327));
+    assert_eq!(buffer.format_xsd_decimal(f64::NAN), None);
+    assert_eq!(buffer.format_xsd_decimal(f64::NEG_INFINITY), None);
+}
+
+#[test]
+fn test_openmetrics() {
+    let mut buffer = dtoa::Buffer::new();
+
+    assert_eq!(buffer.format_openmetrics(1.0), "1.0");
+    assert_eq!(buffer.format_openmetrics(0.005), "0.005");
+    assert_eq!(buffer.format_openmetrics(-2.5f32), "-2.5");
+    assert_eq!(buffer.format_openmetrics(1e30), "1e30");
+    assert_eq!(buffer.format_openmetrics(f64::NAN), "NaN");
+    assert_eq!(buffer.format_openmetrics(f64::INFINITY), "+Inf");
+    assert_eq!(buffer.format_openmetrics(f32::NEG_INFINITY), "-Inf");
+}
+
+#[test]
+fn test_svg() {
+    let mut buffer = dtoa::Buffer::new();
+
+    assert_eq!(buffer.format_svg(0.5, 3), Some(".5"));
+    assert_eq!(buffer.format_svg(-0.5, 3), Some("-.5"));
+    assert_eq!(buffer.format_svg(1.0, 3), Some("1"));
+    assert_eq!(buffer.format_svg(100.0, 3), Some("100"));
+    assert_eq!(buffer.format_svg(1000.0, 3), Some("1e3"));
+    assert_eq!(buffer.format_svg(0.0015, 4), Some(".0015"));
+    assert_eq!(buffer.format_svg(0.00015, 5), Some("15e-5"));
+    assert_eq!(buffer.format_svg(0.1, 20), Some(".1"));
+    assert_eq!(buffer.format_svg(3.14159, 2), Some("3.14"));
+    assert_eq!(buffer.format_svg(9.96, 1), Some("10"));
+    assert_eq!(buffer.format_svg(0.125, 2), Some(".12"));
+    assert_eq!(buffer.format_svg(-0.004, 2), Some("0"));
+    assert_eq!(buffer.format_svg(-0.0, 2), Some("0"));
+    assert_eq!(buffer.format_svg(1e-7f32, 10), Some("1e-7"));
+    assert_eq!(
+        buffer.format_svg(f64::MAX, 0),
+        Some("17976931348623157e292")
+    );
+
+    let join = |values: &[f64]| dtoa::svg::join(values, 2).to_string();
+    assert_eq!(join(&[]), "");
+    assert_eq!(join(&[10.0, 20.0]), "10 20");
+    assert_eq!(join(&[10.0, -0.5, 0.25, 0.75]), "10-.5.25.75");
+    assert_eq!(join(&[1.0, 0.5, 1e3, 0.5]), "1 .5 1e3.5");
+
+    assert_eq!(buffer.format_svg(f64::NAN, 2), None);
+    assert_eq!(buffer.format_svg(f32::INFINITY, 2), None);
+    assert_eq!(buffer.format_svg(f64::NEG_INFINITY, 2), None);
+
+    let mut string = String::new();
+    let join = dtoa::svg::join(&[1.0, f64::NAN], 2);
+    assert!(fmt::write(&mut string, format_args!("{join}")).is_err());
+}
+
+#[test]
+fn test_format_with() {
+    use dtoa::{Layout, Options};
+
+    let mut buffer = dtoa::LargeBuffer::new();
+
+    let standard = Options::new();
+    assert_eq!(buffer.format_with(100000.0, &standard), "100000.0");
+    assert_eq!(buffer.format_with(1e21, &standard), "1e21");
+    assert_eq!(buffer.format_with(f64::NAN, &standard), "NaN");
+
+    let shortest = Options::new().layout(Layout::Shortest);
+    assert_eq!(buffer.format_with(100000.0, &shortest), "1e5");
+    assert_eq!(buffer.format_with(1234.0, &shortest), "1234");
+    assert_eq!(buffer.format_with(1200.0, &shortest), "1200");
+    assert_eq!(buffer.format_with(12000.0, &shortest), "12000");
+    assert_eq!(buffer.format_with(120000.0, &shortest), "1.2e5");
+    assert_eq!(buffer.format_with(0.01, &shortest), "0.01");
+    assert_eq!(buffer.format_with(0.001, &shortest), "1e-3");
+    assert_eq!(buffer.format_with(0.0001, &shortest), "1e-4");
+    assert_eq!(buffer.format_with(0.0012, &shortest), "0.0012");
+    assert_eq!(buffer.format_with(0.00012, &shortest), "1.2e-4");
+    assert_eq!(buffer.format_with(1.5, &shortest), "1.5");
+    assert_eq!(buffer.format_with(-0.0, &shortest), "-0");
+    assert_eq!(buffer.format_with(1e300, &shortest), "1e300");
+    assert_eq!(buffer.format_with(1.5e-300f64, &shortest), "1.5e-300");
+    assert_eq!(buffer.format_with(1e10f32, &shortest), "1e10");
+    assert_eq!(buffer.format_with(f32::NEG_INFINITY, &shortest), "-inf");
+
+    // A Buffer holds the longest output of every layout, but not of the other
+    // exponent styles or digits.
+    let mut small = dtoa::Buffer::new();
+    let scientific = Options::new().layout(Layout::Scientific);
+    let value = -2.4328714240831069e-6;
+    assert_eq!(
+        small.format_with(value, &standard),
+        Some("-0.0000024328714240831069"),
+    );
+    assert_eq!(
+        small.format_with(value, &scientific),
+        Some("-2.4328714240831069e-6"),
+    );
+    assert_eq!(
+        small.format_with(-2.2250738585072014e-308, &shortest),
+        Some("-2.2250738585072014e-308"),
+    );
+    assert_eq!(small.format_with(f64::NAN, &shortest), Some("NaN"));
+    let latex = scientific.exponent_style(dtoa::ExponentStyle::Latex);
+    assert_eq!(small.format_with(1.0, &latex), None);
+    let fullwidth = standard.digits(dtoa::Digits::Fullwidth);
+    assert_eq!(small.format_with(1.0, &fullwidth), None);
+}
+
+#[test]
+fn test_point_zero() {
+    use dtoa::{Layout, Options};
+
+    let mut buffer = dtoa::LargeBuffer::new();
+
+    let options = Options::new().point_zero(false);
+    assert_eq!(buffer.format_with(123.0, &options), "123");
+    assert_eq!(buffer.format_with(0.0, &options), "0");
+    assert_eq!(buffer.format_with(-0.0, &options), "-0");
+    assert_eq!(buffer.format_with(1e20, &options), "100000000000000000000");
+    assert_eq!(buffer.format_with(1e21, &options), "1e21");
+    assert_eq!(buffer.format_with(1.5, &options), "1.5");
+    assert_eq!(buffer.format_with(10.5f32, &options), "10.5");
+    assert_eq!(buffer.format_with(1e-7, &options), "1e-7");
+    assert_eq!(buffer.format_with(f64::INFINITY, &options), "inf");
+
+    let options = options.layout(Layout::Shortest).point_zero(true);
+    assert_eq!(buffer.format_with(123.0, &options), "123");
+}
+
+#[test]
+fn test_toml_yaml() {
+    use dtoa::Options;
+
+    let mut buffer = dtoa::LargeBuffer::new();
+
+    let toml = Options::toml();
+    assert_eq!(buffer.format_with(1.0, &toml), "1.0");
+    assert_eq!(buffer.format_with(1e21, &toml), "1e21");
+    assert_eq!(buffer.format_with(-0.0, &toml), "-0.0");
+    assert_eq!(buffer.format_with(f64::NAN, &toml), "nan");
+    assert_eq!(buffer.format_with(-f64::NAN, &toml), "nan");
+    assert_eq!(buffer.format_with(f64::INFINITY, &toml), "inf");
+    assert_eq!(buffer.format_with(f32::NEG_INFINITY, &toml), "-inf");
+
+    let yaml = Options::yaml();
+    assert_eq!(buffer.format_with(1.0, &yaml), "1.0");
+    assert_eq!(buffer.format_with(1.5e-7, &yaml), "1.5e-7");
+    assert_eq!(buffer.format_with(-0.0, &yaml), "-0.0");
+    assert_eq!(buffer.format_with(f64::NAN, &yaml), ".nan");
+    assert_eq!(buffer.format_with(f64::INFINITY, &yaml), ".inf");
+    assert_eq!(buffer.format_with(f32::NEG_INFINITY, &yaml), "-.inf");
+
+    let custom = Options::new().nonfinite("NaN", "Infinity", "-Infinity");
+    assert_eq!(buffer.format_with(f64::NEG_INFINITY, &custom), "-Infinity");
+}
+
+#[test]
+fn test_postgres() {
+    let mut buffer = dtoa::Buffer::new();
+
+    // Outputs of PostgreSQL 12+ float8out.
+    assert_eq!(buffer.format_postgres(1e16), "1e+16");
+    assert_eq!(buffer.format_postgres(1e15), "1e+15");
+    assert_eq!(buffer.format_postgres(1e14), "100000000000000");
+    assert_eq!(buffer.format_postgres(1.0 / 3.0), "0.3333333333333333");
+    assert_eq!(buffer.format_postgres(0.0001), "0.0001");
+    assert_eq!(buffer.format_postgres(0.00001), "1e-05");
+    assert_eq!(buffer.format_postgres(f64::MAX), "1.7976931348623157e+308");
+    assert_eq!(buffer.format_postgres(5e-324), "5e-324");
+    assert_eq!(buffer.format_postgres(0.0), "0");
+    assert_eq!(buffer.format_postgres(-0.0), "-0");
+    assert_eq!(buffer.format_postgres(f64::NAN), "NaN");
+    assert_eq!(buffer.format_postgres(f64::INFINITY), "Infinity");
+    assert_eq!(buffer.format_postgres(f64::NEG_INFINITY), "-Infinity");
+    // The shorter 6.8441e20, 9.2192e22 and 2.537e21 are the upper boundaries
+    // and 1.9591e21 the lower boundary of these floats, which PostgreSQL
+    // never prints although they parse to the same float.
+    assert_eq!(buffer.format_postgres(6.8441e20), "6.844099999999999e+20");
+    assert_eq!(buffer.format_postgres(-9.2192e22), "-9.219199999999999e+22");
+    assert_eq!(buffer.format_postgres(2.537e21), "2.5369999999999997e+21");
+    assert_eq!(
+        buffer.format_postgres(-1.9591e21),
+        "-1.9591000000000001e+21"
+    );
+
+    // Outputs of PostgreSQL 12+ float4out.
+    assert_eq!(buffer.format_postgres(0.1f32), "0.1");
+    assert_eq!(buffer.format_postgres(123456f32), "123456");
+    assert_eq!(buffer.format_postgres(1234567f32), "1.234567e+06");
+    assert_eq!(buffer.format_postgres(f32::MAX), "3.4028235e+38");
+    assert_eq!(buffer.format_postgres(-1.5e-5f32), "-1.5e-05");
+}
+
+#[test]
+fn test_sql() {
+    use dtoa::sql::Dialect;
+
+    let mut buffer = dtoa::Buffer::new();
+
+    assert_eq!(buffer.format_sql(1.5e10, Dialect::Standard), Ok("1.5E10"));
+    assert_eq!(buffer.format_sql(1.0, Dialect::Standard), Ok("1E0"));
+    assert_eq!(buffer.format_sql(-2.5e-7, Dialect::MySql), Ok("-2.5E-7"));
+    assert_eq!(buffer.format_sql(0.0, Dialect::SqlServer), Ok("0E0"));
+    assert_eq!(buffer.format_sql(123.456, Dialect::Sqlite), Ok("1.23456E2"));
+    assert_eq!(buffer.format_sql(0.1f32, Dialect::PostgreSql), Ok("1E-1"));
+    assert_eq!(buffer.format_sql(1.5, Dialect::Oracle), Ok("1.5E0D"));
+    assert_eq!(buffer.format_sql(1.5f32, Dialect::Oracle), Ok("1.5E0F"));
+
+    assert!(buffer.format_sql(f64::NAN, Dialect::Standard).is_err());
+    assert!(buffer.format_sql(f64::INFINITY, Dialect::MySql).is_err());
+    assert!(buffer
+        .format_sql(f64::NEG_INFINITY, Dialect::SqlServer)
+        .is_err());
+    assert!(buffer.format_sql(f64::NAN, Dialect::Sqlite).is_err());
+    assert_eq!(
+        buffer.format_sql(f64::NEG_INFINITY, Dialect::PostgreSql),
+        Ok("CAST('-Infinity' AS DOUBLE PRECISION)"),
+    );
+    assert_eq!(
+        buffer.format_sql(f32::NAN, Dialect::PostgreSql),
+        Ok("CAST('NaN' AS REAL)"),
+    );
+    assert_eq!(
+        buffer.format_sql(f64::INFINITY, Dialect::Sqlite),
+        Ok("9e999")
+    );
+    assert_eq!(
+        buffer.format_sql(f32::NEG_INFINITY, Dialect::Oracle),
+        Ok("-BINARY_FLOAT_INFINITY"),
+    );
+}
+
+#[test]
+fn test_exponent_style() {
+    use dtoa::{ExponentStyle, Layout, Options};
+
+    let mut buffer = dtoa::LargeBuffer::new();
+
+    let scientific = Options::new().layout(Layout::Scientific);
+    assert_eq!(buffer.format_with(1.23e-5, &scientific), "1.23e-5");
+    assert_eq!(buffer.format_with(100000.0, &scientific), "1e5");
+    assert_eq!(buffer.format_with(1.5, &scientific), "1.5e0");
+    assert_eq!(buffer.format_with(-0.0, &scientific), "-0e0");
+
+    let latex = scientific.exponent_style(ExponentStyle::Latex);
+    assert_eq!(buffer.format_with(1.23e-5, &latex), "1.23 \\times 10^{-5}");
+    assert_eq!(buffer.format_with(-1e300, &latex), "-1 \\times 10^{300}");
+
+    let unicode = scientific.exponent_style(ExponentStyle::Unicode);
+    assert_eq!(buffer.format_with(1.23e-5, &unicode), "1.23×10⁻⁵");
+    assert_eq!(buffer.format_with(6.02e23, &unicode), "6.02×10²³");
+    assert_eq!(buffer.format_with(5e-324, &unicode), "5×10⁻³²⁴");
+    assert_eq!(buffer.format_with(f64::NAN, &unicode), "NaN");
+
+    let standard = Options::new().exponent_style(ExponentStyle::Unicode);
+    assert_eq!(buffer.format_with(1e21, &standard), "1×10²¹");
+    assert_eq!(
+        buffer.format_with(1e20, &standard),
+        "100000000000000000000.0"
+    );
+}
+
+#[test]
+fn test_digits() {
+    use dtoa::{Digits, ExponentStyle, Options};
+
+    let mut buffer = dtoa::LargeBuffer::new();
+
+    let arabic = Options::new().digits(Digits::ArabicIndic);
+    assert_eq!(buffer.format_with(1234.5, &arabic), "١٢٣٤.٥");
+    assert_eq!(buffer.format_with(1e21, &arabic), "١e٢١");
+    assert_eq!(buffer.format_with(f64::NAN, &arabic), "NaN");
+
+    let persian = Options::new().digits(Digits::ExtendedArabicIndic);
+    assert_eq!(buffer.format_with(0.25f32, &persian), "۰.۲۵");
+
+    let devanagari = Options::new().digits(Digits::Devanagari);
+    assert_eq!(buffer.format_with(-1e-7, &devanagari), "-१e-७");
+
+    let fullwidth = Options::new().digits(Digits::Fullwidth);
+    assert_eq!(buffer.format_with(-0.0, &fullwidth), "-０.０");
+    assert_eq!(
+        buffer.format_with(-1.2345678901234567e-308, &fullwidth),
+        "-１.２３４５６７８９０１２３４５６７e-３０８",
+    );
+
+    let superscript = fullwidth.exponent_style(ExponentStyle::Unicode);
+    assert_eq!(buffer.format_with(1e21, &superscript), "１×10²¹");
+
+    // The base 10 of the exponent stays ASCII.
+    let latex = Options::new()
+        .layout(dtoa::Layout::Scientific)
+        .exponent_style(ExponentStyle::Latex)
+        .digits(Digits::Devanagari);
+    assert_eq!(
+        buffer.format_with(-1.25e-10, &latex),
+        "-१.२५ \\times 10^{-१०}",
+    );
+}
+
+#[test]
+fn test_utf16() {
+    let mut buffer = dtoa::Buffer16::new();
+    let mut format = |value: f64| String::from_utf16(buffer.format_utf16(value)).unwrap();
+
+    assert_eq!(format(1.234), "1.234");
+    assert_eq!(format(-1.234e20), "-123400000000000000000.0");
+    assert_eq!(format(1.234e21), "1.234e21");
+    assert_eq!(format(-2.2250738585072014e-308), "-2.2250738585072014e-308");
+    assert_eq!(format(1e-7), "1e-7");
+    assert_eq!(format(0.0), "0.0");
+    assert_eq!(format(f64::NAN), "NaN");
+    assert_eq!(format(f64::NEG_INFINITY), "-inf");
+
+    let mut buffer = dtoa::Buffer16::new();
+    let printed = buffer.format_utf16(1.5e-5f32);
+    assert_eq!(String::from_utf16(printed).unwrap(), "0.000015");
+}
+
+#[test]
+fn test_format_to_slice() {
+    use dtoa::Float;
+    use std::mem::MaybeUninit;
+
+    fn format_to_slice<F: Float>(value: F, len: usize) -> Result<String, dtoa::BufferTooSmall> {
+        let mut slice = vec![MaybeUninit::<u8>::uninit(); len];
+        let written = dtoa::format_to_slice(value, &mut slice)?;
+        let bytes = slice[..written]
+            .iter()
+            .map(|byte| unsafe { byte.assume_init() })
+            .collect();
+        Ok(String::from_utf8(bytes).unwrap())
+    }
+
+    assert_eq!(f32::MAX_LEN, 24);
+    assert_eq!(f64::MAX_LEN, 25);
+
+    let longest = -1.2345678901234567e-6;
+    assert_eq!(format_to_slice(longest, 25).unwrap().len(), f64::MAX_LEN);
+    assert_eq!(format_to_slice(-1e20f32, 24).unwrap().len(), f32::MAX_LEN);
+    assert_eq!(format_to_slice(1.5, 64).unwrap(), "1.5");
+    assert_eq!(format_to_slice(1.5, 3).unwrap(), "1.5");
+    assert!(format_to_slice(1.5, 2).is_err());
+    assert_eq!(format_to_slice(f64::NAN, 3).unwrap(), "NaN");
+    assert!(format_to_slice(f64::NEG_INFINITY, 3).is_err());
+    assert!(format_to_slice(0.0f32, 0).is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_append() {
+    let mut vec = b"[".to_vec();
+    dtoa::append(&mut vec, 1.5);
+    vec.push(b',');
+    dtoa::append(&mut vec, -2.5e-7);
+    vec.push(b',');
+    dtoa::append(&mut vec, f32::NAN);
+    vec.push(b']');
+    assert_eq!(vec, b"[1.5,-2.5e-7,NaN]");
+
+    let mut string = String::new();
+    dtoa::append_string(&mut string, 1e21);
+    dtoa::append_string(&mut string, f64::NEG_INFINITY);
+    assert_eq!(string, "1e21-inf");
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bufmut() {
+    use bytes::{BufMut, BytesMut};
+    use dtoa::BufMutExt;
+
+    let mut frame = BytesMut::with_capacity(4);
+    frame.put_f64_text(-1.5e-7);
+    frame.put_u8(b' ');
+    frame.put_f32_text(f32::INFINITY);
+    frame.put_u8(b' ');
+    frame.put_f64_text(123456789.0);
+    assert_eq!(frame, &b"-1.5e-7 inf 123456789.0"[..]);
+
+    // Output that straddles the chunks of a chained buffer.
+    let mut first = [0u8; 3];
+    let mut second = [0u8; 8];
+    let mut chain = (&mut first[..]).chain_mut(&mut second[..]);
+    chain.put_f64_text(1.2345);
+    assert_eq!(&first, b"1.2");
+    assert_eq!(&second[..3], b"345");
+}
+
+#[test]
+fn test_formatted() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    struct Row {
+        label: dtoa::Formatted,
+    }
+
+    let row = Row {
+        label: dtoa::to_string_inline(-1.5e-7),
+    };
+    let copy = row.label;
+    assert_eq!(&*row.label, "-1.5e-7");
+    assert_eq!(copy, row.label);
+    assert_eq!(row.label.to_string(), "-1.5e-7");
+    assert_eq!(format!("{:?}", row.label), "\"-1.5e-7\"");
+    assert_eq!(AsRef::<[u8]>::as_ref(&row.label), b"-1.5e-7");
+    assert_eq!(hash(&row.label), hash("-1.5e-7"));
+
+    let longest = dtoa::to_string_inline(-1.2345678901234567e-6);
+    assert_eq!(longest.len(), 25);
+    assert_eq!(&*dtoa::to_string_inline(f32::NAN), "NaN");
+    assert!(dtoa::to_string_inline(1.5) < dtoa::to_string_inline(1.55));
+    assert!(dtoa::to_string_inline(10.0) < dtoa::to_string_inline(2.0));
+}
+
+#[test]
+fn test_format_with_info() {
+    let mut buffer = dtoa::Buffer::new();
+
+    let (printed, info) = buffer.format_with_info(-0.00123);
+    assert_eq!(printed, "-0.00123");
+    let info = info.unwrap();
+    assert!(!info.is_exponential());
+    assert_eq!(info.significant_digits(), 3);
+    assert_eq!(info.exponent(), -3);
+    assert!(!info.is_integral());
+
+    let (printed, info) = buffer.format_with_info(1200.0);
+    assert_eq!(printed, "1200.0");
+    let info = info.unwrap();
+    assert!(!info.is_exponential());
+    assert_eq!(info.significant_digits(), 2);
+    assert_eq!(info.exponent(), 3);
+    assert!(info.is_integral());
+
+    let (printed, info) = buffer.format_with_info(1.5e-7f32);
+    assert_eq!(printed, "1.5e-7");
+    let info = info.unwrap();
+    assert!(info.is_exponential());
+    assert_eq!(info.exponent(), -7);
+    assert!(!info.is_integral());
+
+    let (printed, info) = buffer.format_with_info(0.0);
+    assert_eq!(printed, "0.0");
+    let info = info.unwrap();
+    assert_eq!(info.significant_digits(), 1);
+    assert_eq!(info.exponent(), 0);
+    assert!(info.is_integral());
+
+    assert_eq!(buffer.format_with_info(f64::NAN), ("NaN", None));
+}
+
+#[test]
+fn test_formatted_len() {
+    let mut buffer = dtoa::Buffer::new();
+    for value in [
+        0.0,
+        -0.0,
+        1.0,
+        -1200.0,
+        9007199254740993.0,
+        1e21,
+        1e22,
+        1.5,
+        0.3,
+        -0.000001234567890123456,
+        1.2345e-7,
+        5e-324,
+        f64::MAX,
+        f64::NAN,
+        f64::NEG_INFINITY,
+    ] {
+        assert_eq!(dtoa::formatted_len(value), buffer.format(value).len());
+    }
+    for value in [0.1f32, -16777217.0, 1e-10, f32::MAX, f32::INFINITY] {
+        assert_eq!(dtoa::formatted_len(value), buffer.format(value).len());
+    }
+}
+
+#[test]
+fn test_format_fit() {
+    let mut buffer = dtoa::LargeBuffer::new();
+    assert_eq!(buffer.format_fit(1234.5, 10), Some("1234.5"));
+    assert_eq!(buffer.format_fit(1234.5, 4), Some("1234"));
+    assert_eq!(buffer.format_fit(1234.5, 3), Some("1e3"));
+    assert_eq!(buffer.format_fit(9.96, 3), Some("10"));
+    assert_eq!(buffer.format_fit(0.125, 4), Some("0.12"));
+    assert_eq!(buffer.format_fit(-0.000123456, 8), Some("-1.23e-4"));
+    assert_eq!(buffer.format_fit(1e21, 5), Some("1e21"));
+    assert_eq!(buffer.format_fit(0.0, 1), Some("0"));
+    assert_eq!(buffer.format_fit(-0.0, 1), None);
+    assert_eq!(buffer.format_fit(5e-324, 10), Some("5e-324"));
+    assert_eq!(buffer.format_fit(0.1f32, 10), Some("0.1"));
+    assert_eq!(buffer.format_fit(f64::NEG_INFINITY, 4), Some("-inf"));
+    assert_eq!(buffer.format_fit(f64::NAN, 2), None);
+}
+
+#[test]
+fn test_const_format() {
+    const HALF: &str = dtoa::const_format!(0.5);
+    const TINY: &str = dtoa::const_format!(-5e-324);
+    const LARGE: &str = dtoa::const_format!(1.7976931348623157e308);
+    const NAN: &str = dtoa::const_format!(f64::NAN);
+    const SINGLE: dtoa::Formatted = dtoa::Formatted::from_f32(0.3);
+
+    assert_eq!(HALF, "0.5");
+    assert_eq!(TINY, "-5e-324");
+    assert_eq!(LARGE, "1.7976931348623157e308");
+    assert_eq!(NAN, "NaN");
+    assert_eq!(SINGLE.as_str(), "0.3");
+
+    let mut buffer = dtoa::Buffer::new();
+    for value in [
+        0.0,
+        -0.0,
+        1.0,
+        0.1,
+        123456.789,
+        1e21,
+        1e-7,
+        2.2250738585072014e-308,
+    ] {
+        assert_eq!(
+            dtoa::Formatted::from_f64(value).as_str(),
+            buffer.format(value)
+        );
+    }
+    for value in [0.1f32, -16777217.0, 1e-45, f32::MAX, f32::NEG_INFINITY] {
+        assert_eq!(
+            dtoa::Formatted::from_f32(value).as_str(),
+            buffer.format(value)
+        );
+    }
+}
+
+#[test]
+fn test_const_format_exponents() {
+    let mut buffer = dtoa::Buffer::new();
+    for exponent in 0..=0x7ff_u64 {
+        for significand in [0, 1, 0x8000000000000, 0xfffffffffffff] {
+            for sign in [0, 1 << 63] {
+                let value = f64::from_bits(sign | exponent << 52 | significand);
+                assert_eq!(
+                    dtoa::Formatted::from_f64(value).as_str(),
+                    buffer.format(value)
+                );
+            }
+        }
+    }
+    for exponent in 0..=0xff_u32 {
+        for significand in [0, 1, 0x400000, 0x7fffff] {
+            for sign in [0, 1 << 31] {
+                let value = f32::from_bits(sign | exponent << 23 | significand);
+                assert_eq!(
+                    dtoa::Formatted::from_f32(value).as_str(),
+                    buffer.format(value)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_format_exact() {
+    let mut buffer = dtoa::ExactBuffer::new();
+    assert_eq!(
+        buffer.format_exact(0.1),
+        "0.1000000000000000055511151231257827021181583404541015625",
+    );
+    assert_eq!(buffer.format_exact(1.0), "1.0");
+    assert_eq!(buffer.format_exact(-2.5), "-2.5");
+    assert_eq!(buffer.format_exact(0.0), "0.0");
+    assert_eq!(buffer.format_exact(1e23), "99999999999999991611392.0");
+    assert_eq!(buffer.format_exact(0.1f32), "0.100000001490116119384765625");
+    assert_eq!(buffer.format_exact(f64::NEG_INFINITY), "-inf");
+
+    let printed = buffer.format_exact(-5e-324);
+    assert_eq!(printed.len(), 1077);
+    assert!(printed.starts_with("-0.000"));
+    assert!(printed.ends_with("47265625"));
+    assert_eq!(printed.parse::<f64>(), Ok(-5e-324));
+
+    let printed = buffer.format_exact(f64::MAX);
+    assert_eq!(printed.len(), 311);
+    assert!(printed.starts_with("17976931348623157081"));
+    assert_eq!(printed.parse::<f64>(), Ok(f64::MAX));
+}